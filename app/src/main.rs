@@ -1,3 +1,240 @@
-fn main() {
+use std::{env, path::PathBuf, process};
+
+use lib_simulation as sim;
+use rand::SeedableRng;
+
+const USAGE: &str = "\
+Usage: app [options]
+
+Options:
+  --animals <n>         Number of birds (default: 40)
+  --foods <n>           Number of foods (default: 60)
+  --generations <n>     Generations to train (default: 100)
+  --seed <n>            Seed of the RNG (default: 0)
+  --checkpoint <path>   Write a checkpoint to <path>
+  --every <n>           Generations between checkpoints (default: 10)
+  --resume <path>       Continue from a checkpoint
+  --hall-of-fame <path> Save the best birds to <path> when done
+  --fitness <name>      How birds are scored: default, food, efficiency
+                        (food per distance) or survival
+  --see-birds           Let birds see each other, so they can flock
+  --inputs <names>      Also feed the brain some of speed, compass,
+                        energy, hunger and clock, comma separated
+  --actions             Let the brain decide when to eat and boost, and
+                        signal to other birds with --see-birds
+  --hidden <layers>     Hidden layers of the brain, as comma separated
+                        <neurons>[:<activation>], e.g. 18:tanh,9; the
+                        activation is relu, sigmoid, tanh or identity
+                        (default: relu), and `none` leaves no hidden
+                        layers (default: one of 18 relu neurons)";
+
+struct Args {
+    animals: usize,
+    foods: usize,
+    generations: usize,
+    seed: u64,
+    checkpoint: Option<PathBuf>,
+    every: usize,
+    resume: Option<PathBuf>,
+    hall_of_fame: Option<PathBuf>,
+    fitness: Option<String>,
+    see_birds: bool,
+    inputs: sim::BrainInputs,
+    actions: bool,
+    hidden: Vec<sim::HiddenLayer>,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Self {
+            animals: 40,
+            foods: 60,
+            generations: 100,
+            seed: 0,
+            checkpoint: None,
+            every: 10,
+            resume: None,
+            hall_of_fame: None,
+            fitness: None,
+            see_birds: false,
+            inputs: sim::BrainInputs::default(),
+            actions: false,
+            hidden: sim::Config::default().brain_hidden_layers,
+        };
+
+        // They shape a new world, which a resumed one already has
+        let mut new_world_args = Vec::new();
+        let mut argv = env::args().skip(1);
+
+        while let Some(arg) = argv.next() {
+            if matches!(
+                arg.as_str(),
+                "--animals" | "--foods" | "--see-birds" | "--inputs" | "--actions" | "--hidden"
+            ) {
+                new_world_args.push(arg.clone());
+            }
+
+            let mut value = || argv.next().ok_or(format!("missing value for {arg}"));
+
+            match arg.as_str() {
+                "--animals" => args.animals = parse(&value()?)?,
+                "--foods" => args.foods = parse(&value()?)?,
+                "--generations" => args.generations = parse(&value()?)?,
+                "--seed" => args.seed = parse(&value()?)?,
+                "--checkpoint" => args.checkpoint = Some(value()?.into()),
+                "--every" => args.every = parse(&value()?)?,
+                "--resume" => args.resume = Some(value()?.into()),
+                "--hall-of-fame" => args.hall_of_fame = Some(value()?.into()),
+                "--fitness" => args.fitness = Some(value()?),
+                "--see-birds" => args.see_birds = true,
+                "--inputs" => args.inputs = parse_inputs(&value()?)?,
+                "--actions" => args.actions = true,
+                "--hidden" => args.hidden = parse_hidden(&value()?)?,
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
+
+        if args.every == 0 {
+            return Err("--every must be at least 1".into());
+        }
+
+        if args.resume.is_some() && !new_world_args.is_empty() {
+            return Err(format!(
+                "{} can't be combined with --resume",
+                new_world_args.join(", ")
+            ));
+        }
+
+        Ok(args)
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number: {value}"))
+}
+
+fn parse_hidden(value: &str) -> Result<Vec<sim::HiddenLayer>, String> {
+    if value == "none" {
+        return Ok(Vec::new());
+    }
+
+    value
+        .split(',')
+        .map(|layer| {
+            let (neurons, activation) = layer.split_once(':').unwrap_or((layer, "relu"));
+
+            let activation = match activation {
+                "relu" => sim::Activation::Relu,
+                "sigmoid" => sim::Activation::Sigmoid,
+                "tanh" => sim::Activation::Tanh,
+                "identity" => sim::Activation::Identity,
+                other => return Err(format!("unknown activation: {other}")),
+            };
+
+            Ok(sim::HiddenLayer {
+                neurons: parse(neurons)?,
+                activation,
+            })
+        })
+        .collect()
 }
 
+fn parse_inputs(value: &str) -> Result<sim::BrainInputs, String> {
+    let mut inputs = sim::BrainInputs::default();
+
+    for input in value.split(',') {
+        let enabled = match input {
+            "speed" => &mut inputs.speed,
+            "compass" => &mut inputs.compass,
+            "energy" => &mut inputs.energy,
+            "hunger" => &mut inputs.hunger,
+            "clock" => &mut inputs.clock,
+            other => return Err(format!("unknown input: {other}")),
+        };
+        *enabled = true;
+    }
+
+    Ok(inputs)
+}
+
+fn main() {
+    let args = Args::parse().unwrap_or_else(|err| {
+        eprintln!("{err}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    let (mut simulation, mut rng) = match &args.resume {
+        Some(path) => {
+            let (simulation, rng) = sim::Simulation::resume(path).unwrap_or_else(|err| {
+                eprintln!("could not resume from {}: {err}", path.display());
+                process::exit(1);
+            });
+
+            let rng = rng.unwrap_or_else(|| sim::ChaCha8Rng::seed_from_u64(args.seed));
+            (simulation, rng)
+        }
+        None => {
+            let mut rng = sim::ChaCha8Rng::seed_from_u64(args.seed);
+            let config = sim::Config {
+                eye_birds: args.see_birds,
+                brain_inputs: args.inputs,
+                brain_outputs: sim::BrainOutputs {
+                    eat: args.actions,
+                    boost: args.actions,
+                    signal: args.actions && args.see_birds,
+                },
+                brain_hidden_layers: args.hidden,
+                ..sim::Config::default()
+            };
+            let simulation =
+                sim::Simulation::random_with_config(&mut rng, config, args.animals, args.foods)
+                    .unwrap_or_else(|err| {
+                        eprintln!("{err}\n\n{USAGE}");
+                        process::exit(2);
+                    });
+            (simulation, rng)
+        }
+    };
+
+    match args.fitness.as_deref() {
+        None | Some("default") => {}
+        Some("food") => simulation.set_fitness(sim::Satiation),
+        Some("efficiency") => simulation.set_fitness(sim::FoodPerDistance),
+        Some("survival") => simulation.set_fitness(sim::Survival),
+        Some(other) => {
+            eprintln!("unknown fitness: {other}\n\n{USAGE}");
+            process::exit(2);
+        }
+    }
+
+    if let Some(path) = &args.checkpoint {
+        simulation.enable_checkpoints(args.every, path);
+    }
+
+    for _ in 0..args.generations {
+        let (stats, info) = simulation
+            .train_checkpointed(&mut rng)
+            .unwrap_or_else(|err| {
+                eprintln!("could not write checkpoint: {err}");
+                process::exit(1);
+            });
+
+        println!(
+            "generation {}: min={:.2} max={:.2} avg={:.2} cohesion={:.3}",
+            info.generation(),
+            stats.min_fitness,
+            stats.max_fitness,
+            stats.average_fitness,
+            info.cohesion()
+        );
+    }
+
+    if let Some(path) = &args.hall_of_fame
+        && let Err(err) = simulation.hall_of_fame().save(path)
+    {
+        eprintln!("could not save hall of fame to {}: {err}", path.display());
+        process::exit(1);
+    }
+}
@@ -0,0 +1,65 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use lib_genetic_algorithm::{
+    Crhomosome, GaussianMutation, GeneticAlgorithm, Individual, RouletteWheelSelection,
+    UniformCrossover,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Genes of a default bird brain: 9 eye cells, 18 hidden neurons and
+/// the speed and rotation outputs
+const GENES: usize = (9 + 1) * 18 + (18 + 1) * 2;
+
+struct BenchIndividual {
+    chromosome: Crhomosome,
+    fitness: f32,
+}
+
+impl Individual for BenchIndividual {
+    fn create(chromosome: Crhomosome) -> Self {
+        Self {
+            chromosome,
+            fitness: 1.0,
+        }
+    }
+
+    fn chromosome(&self) -> &Crhomosome {
+        &self.chromosome
+    }
+
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+}
+
+fn envolve(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+    let mut ga = GeneticAlgorithm::new(
+        RouletteWheelSelection,
+        UniformCrossover,
+        GaussianMutation::new(0.01, 0.3),
+    );
+    let mut group = c.benchmark_group("envolve");
+
+    for size in [20, 100, 500] {
+        let population: Vec<_> = (0..size)
+            .map(|_| BenchIndividual {
+                chromosome: (0..GENES).map(|_| rng.random_range(-1.0..=1.0)).collect(),
+                fitness: rng.random_range(0.0..10.0),
+            })
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &population,
+            |b, population| b.iter(|| ga.envolve(&mut rng, black_box(population))),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, envolve);
+criterion_main!(benches);
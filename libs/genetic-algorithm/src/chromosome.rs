@@ -1,6 +1,8 @@
 use std::ops::Index;
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Crhomosome {
     pub genes: Vec<f32>,
 }
@@ -16,6 +18,10 @@ impl Crhomosome {
         self.genes.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.genes.is_empty()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &f32> {
         self.genes.iter()
     }
@@ -1,7 +1,9 @@
 use crate::chromosome::Crhomosome;
 
 pub trait Individual {
-    fn create(chromosome: Crhomosome) -> Self;
+    fn create(chromosome: Crhomosome) -> Self
+    where
+        Self: Sized;
     fn fitness(&self) -> f32;
     fn chromosome(&self) -> &Crhomosome;
 }
@@ -2,6 +2,7 @@ mod chromosome;
 mod crossover;
 mod individual;
 mod mutation;
+mod observer;
 mod selection;
 mod statistics;
 
@@ -10,19 +11,20 @@ pub use chromosome::Crhomosome;
 pub use crossover::UniformCrossover;
 pub use individual::Individual;
 pub use mutation::GaussianMutation;
-pub use selection::RouletteWheelSelection;
+pub use observer::Observer;
+pub use selection::{RouletteWheelSelection, SelectionMethod, TournamentSelection};
 pub use statistics::Statistics;
 
 // === Internal ===
 use crossover::CrossoverMethod;
 use mutation::MutationMethod;
 use rand::RngCore;
-use selection::SelectionMethod;
 
 pub struct GeneticAlgorithm<S> {
     selection_method: S,
     crossover_method: Box<dyn CrossoverMethod>,
     mutation_method: Box<dyn MutationMethod>,
+    observers: Vec<Box<dyn Observer>>,
 }
 
 impl<S> GeneticAlgorithm<S>
@@ -38,28 +40,68 @@ where
             selection_method,
             crossover_method: Box::new(crossover_method),
             mutation_method: Box::new(mutation_method),
+            observers: Vec::new(),
         }
     }
 
-    pub fn envolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    /// Register an observer that gets notified on every call to `envolve`
+    pub fn add_observer(&mut self, observer: impl Observer + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    pub fn envolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
     where
         I: Individual,
     {
-        let new_population = (0..population.len())
+        let stats = Statistics::new(population);
+
+        if !self.observers.is_empty() {
+            let population: Vec<&dyn Individual> = population.iter().map(|i| i as _).collect();
+            for observer in &mut self.observers {
+                observer.on_evaluated(&population, &stats);
+            }
+        }
+
+        let new_population: Vec<I> = (0..population.len())
             .map(|_| {
                 // Selection
                 let parent_a = self.selection_method.select(rng, population).chromosome();
                 let parent_b = self.selection_method.select(rng, population).chromosome();
-                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
-                self.mutation_method.mutate(rng, &mut child);
-                I::create(child)
+                I::create(self.child(rng, parent_a, parent_b))
             })
             .collect();
 
-        let stats = Statistics::new(&population);
+        if !self.observers.is_empty() {
+            let population: Vec<&dyn Individual> = new_population.iter().map(|i| i as _).collect();
+            for observer in &mut self.observers {
+                observer.on_new_population(&population);
+            }
+        }
 
         (new_population, stats)
     }
+
+    /// Breed a single child of `parent` and a mate picked from
+    /// `population`, to evolve one individual at a time instead of whole
+    /// generations
+    pub fn breed<I>(&self, rng: &mut dyn RngCore, parent: &I, population: &[I]) -> I
+    where
+        I: Individual,
+    {
+        let mate = self.selection_method.select(rng, population).chromosome();
+        I::create(self.child(rng, parent.chromosome(), mate))
+    }
+
+    fn child(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Crhomosome,
+        parent_b: &Crhomosome,
+    ) -> Crhomosome {
+        let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+        self.mutation_method.mutate(rng, &mut child);
+        child
+    }
 }
 
 #[cfg(test)]
@@ -72,35 +114,37 @@ mod tests {
         selection::RouletteWheelSelection,
     };
 
-    use super::*;
+    use std::{cell::RefCell, rc::Rc};
 
-    #[test]
-    fn genetic_algorithm() {
-        #[derive(Clone, Debug, PartialEq)]
-        struct TestIndividual {
-            chromosome: Crhomosome,
-        }
+    use super::*;
 
-        impl Individual for TestIndividual {
-            fn create(chromosome: Crhomosome) -> Self {
-                Self { chromosome }
-            }
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestIndividual {
+        chromosome: Crhomosome,
+    }
 
-            fn chromosome(&self) -> &Crhomosome {
-                &self.chromosome
-            }
+    impl Individual for TestIndividual {
+        fn create(chromosome: Crhomosome) -> Self {
+            Self { chromosome }
+        }
 
-            fn fitness(&self) -> f32 {
-                self.chromosome().iter().sum()
-            }
+        fn chromosome(&self) -> &Crhomosome {
+            &self.chromosome
         }
 
-        fn individual(genes: &[f32]) -> TestIndividual {
-            TestIndividual::create(genes.iter().cloned().collect())
+        fn fitness(&self) -> f32 {
+            self.chromosome().iter().sum()
         }
+    }
 
+    fn individual(genes: &[f32]) -> TestIndividual {
+        TestIndividual::create(genes.iter().cloned().collect())
+    }
+
+    #[test]
+    fn genetic_algorithm() {
         let mut rng = ChaCha8Rng::from_seed(Default::default());
-        let ga = GeneticAlgorithm::new(
+        let mut ga = GeneticAlgorithm::new(
             RouletteWheelSelection,
             UniformCrossover,
             GaussianMutation::new(0.5, 0.5),
@@ -114,7 +158,7 @@ mod tests {
         ];
 
         for _ in 0..10 {
-            population = ga.envolve(&mut rng, &population);
+            (population, _) = ga.envolve(&mut rng, &population);
         }
 
         let expected_population = vec![
@@ -126,4 +170,65 @@ mod tests {
 
         assert_eq!(population, expected_population);
     }
+
+    #[test]
+    fn observer() {
+        #[derive(Default)]
+        struct TestObserver {
+            events: Rc<RefCell<Vec<String>>>,
+        }
+
+        impl Observer for TestObserver {
+            fn on_evaluated(&mut self, population: &[&dyn Individual], stats: &Statistics) {
+                self.events.borrow_mut().push(format!(
+                    "evaluated {} (max {})",
+                    population.len(),
+                    stats.max_fitness
+                ));
+            }
+
+            fn on_new_population(&mut self, population: &[&dyn Individual]) {
+                self.events
+                    .borrow_mut()
+                    .push(format!("new population {}", population.len()));
+            }
+        }
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let observer = TestObserver::default();
+        let events = observer.events.clone();
+
+        let mut ga = GeneticAlgorithm::new(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+        ga.add_observer(observer);
+
+        let population = vec![individual(&[1.0, 1.0]), individual(&[2.0, 3.0])];
+        ga.envolve(&mut rng, &population);
+
+        assert_eq!(
+            *events.borrow(),
+            vec!["evaluated 2 (max 5)", "new population 2"]
+        );
+    }
+
+    #[test]
+    fn breed() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.0, 0.0),
+        );
+
+        let parent = individual(&[0.0, 0.0, 0.0, 0.0]);
+        let population = vec![individual(&[1.0, 1.0, 1.0, 1.0])];
+        let child = ga.breed(&mut rng, &parent, &population);
+
+        // Without mutations, every gene comes from either parent
+        assert_eq!(child.chromosome().len(), 4);
+        assert!(child.chromosome().iter().all(|&g| g == 0.0 || g == 1.0));
+    }
 }
@@ -20,7 +20,7 @@ pub struct GaussianMutation {
 
 impl GaussianMutation {
     pub fn new(chance: f32, coeff: f32) -> Self {
-        assert!(chance >= 0.0 && chance <= 1.0);
+        assert!((0.0..=1.0).contains(&chance));
         Self { chance, coeff }
     }
 }
@@ -48,7 +48,7 @@ mod tests {
 
         fn actual(chance: f32, coeff: f32) -> Vec<f32> {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let mut child = (1..=5).map(|n| n as f32).into_iter().collect();
+            let mut child = (1..=5).map(|n| n as f32).collect();
 
             GaussianMutation::new(chance, coeff).mutate(&mut rng, &mut child);
             child.into_iter().collect()
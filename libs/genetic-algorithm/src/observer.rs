@@ -0,0 +1,14 @@
+use crate::{individual::Individual, statistics::Statistics};
+
+/// Hooks called by [`crate::GeneticAlgorithm::envolve`].
+///
+/// Every hook has an empty default, so an observer only implements the
+/// ones it cares about.
+pub trait Observer {
+    /// Called with the population that was just evaluated, before the
+    /// new one is bred.
+    fn on_evaluated(&mut self, _population: &[&dyn Individual], _stats: &Statistics) {}
+
+    /// Called with the freshly bred population.
+    fn on_new_population(&mut self, _population: &[&dyn Individual]) {}
+}
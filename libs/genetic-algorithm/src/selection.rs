@@ -1,5 +1,6 @@
 use crate::individual::Individual;
-use rand::{RngCore, seq::IndexedRandom};
+use rand::{RngCore, distr::weighted::Error as WeightError, seq::IndexedRandom};
+use std::cmp::Ordering;
 
 pub trait SelectionMethod {
     fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
@@ -7,6 +8,8 @@ pub trait SelectionMethod {
         I: Individual;
 }
 
+/// Picks individuals with a chance proportional to their fitness, which
+/// can't be negative; when every fitness is zero, any of them is as good.
 pub struct RouletteWheelSelection;
 
 impl SelectionMethod for RouletteWheelSelection {
@@ -14,16 +17,51 @@ impl SelectionMethod for RouletteWheelSelection {
     where
         I: Individual,
     {
-        population
-            .choose_weighted(rng, |i| i.fitness())
-            .expect("got an empy population")
+        match population.choose_weighted(rng, |i| i.fitness()) {
+            Ok(individual) => individual,
+            Err(WeightError::InsufficientNonZero) => {
+                population.choose(rng).expect("got an empy population")
+            }
+            Err(err) => panic!("can't weight the population by fitness: {err}"),
+        }
+    }
+}
+
+/// Picks `size` random individuals and returns the fittest of them.
+///
+/// Unlike the roulette wheel, it only cares about the ordering of
+/// fitnesses, so it also works when every fitness is zero.
+pub struct TournamentSelection {
+    size: usize,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+        Self { size }
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        (0..self.size)
+            .map(|_| population.choose(rng).expect("got an empy population"))
+            .max_by(|a, b| {
+                a.fitness()
+                    .partial_cmp(&b.fitness())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .expect("tournament size is not zero")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::chromosome::Crhomosome;
-    use crate::selection::RouletteWheelSelection;
+    use crate::selection::{RouletteWheelSelection, TournamentSelection};
 
     use super::*;
     use rand::SeedableRng;
@@ -81,4 +119,42 @@ mod tests {
         let expected_histogram = BTreeMap::from_iter([(1, 98), (2, 202), (3, 278), (4, 422)]);
         assert_eq!(actual_histogram, expected_histogram);
     }
+
+    #[test]
+    fn roulette_wheel_selection_without_fitness() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let population = vec![TestIndividual::new(0.0), TestIndividual::new(0.0)];
+
+        let picked: Vec<_> = (0..100)
+            .map(|_| RouletteWheelSelection.select(&mut rng, &population))
+            .map(|individual| std::ptr::eq(individual, &population[0]))
+            .collect();
+
+        assert!(picked.contains(&true) && picked.contains(&false));
+    }
+
+    #[test]
+    fn tournament_selection() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(3.0),
+        ];
+
+        let mut actual_histogram = BTreeMap::new();
+
+        for _ in 0..1000 {
+            let fitness = TournamentSelection::new(2)
+                .select(&mut rng, &population)
+                .fitness() as i32;
+
+            *actual_histogram.entry(fitness).or_insert(0) += 1;
+        }
+
+        let expected_histogram = BTreeMap::from_iter([(1, 64), (2, 191), (3, 288), (4, 457)]);
+        assert_eq!(actual_histogram, expected_histogram);
+    }
 }
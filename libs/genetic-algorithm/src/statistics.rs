@@ -1,5 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use crate::Individual;
 
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Statistics {
     pub min_fitness: f32,
     pub max_fitness: f32,
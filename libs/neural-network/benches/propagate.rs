@@ -0,0 +1,63 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use lib_neural_network::{LayerTopology, Network, Scratch};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Same shape as a bird's brain: eye cells in, one hidden layer of two
+/// neurons per cell, and the speed and rotation outputs
+fn network(rng: &mut ChaCha8Rng, cells: usize) -> Network {
+    let layers = [
+        LayerTopology::new(cells),
+        LayerTopology::new(2 * cells),
+        LayerTopology::new(2),
+    ];
+
+    Network::random(rng, &layers)
+}
+
+fn propagate(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+    let mut group = c.benchmark_group("propagate");
+
+    for cells in [9, 32, 128] {
+        let network = network(&mut rng, cells);
+        let inputs: Vec<f32> = (0..cells).map(|_| rng.random()).collect();
+
+        group.bench_with_input(BenchmarkId::new("owned", cells), &inputs, |b, inputs| {
+            b.iter(|| network.propagate(black_box(inputs.clone())))
+        });
+
+        let mut scratch = Scratch::default();
+        group.bench_with_input(BenchmarkId::new("scratch", cells), &inputs, |b, inputs| {
+            b.iter(|| {
+                black_box(network.propagate_with(black_box(inputs), &mut scratch));
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn propagate_batch(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+    let network = network(&mut rng, 9);
+    let mut scratch = Scratch::default();
+    let mut group = c.benchmark_group("propagate_batch");
+
+    for batch in [1, 100, 1000] {
+        let inputs: Vec<f32> = (0..batch * 9).map(|_| rng.random()).collect();
+
+        group.bench_with_input(BenchmarkId::from_parameter(batch), &inputs, |b, inputs| {
+            b.iter(|| {
+                black_box(network.propagate_batch(black_box(inputs), &mut scratch));
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, propagate, propagate_batch);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+/// What a neuron does with the weighted sum of its inputs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Activation {
+    /// `max(0, x)`
+    #[default]
+    Relu,
+    /// `1 / (1 + e^-x)`, from 0 to 1
+    Sigmoid,
+    /// From -1 to 1
+    Tanh,
+    /// `x` as is
+    Identity,
+}
+
+impl Activation {
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            Activation::Relu => x.max(0.0),
+            Activation::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Activation::Tanh => x.tanh(),
+            Activation::Identity => x,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        assert_eq!(Activation::Relu.apply(-2.0), 0.0);
+        assert_eq!(Activation::Relu.apply(2.0), 2.0);
+        assert_eq!(Activation::Sigmoid.apply(0.0), 0.5);
+        assert_eq!(Activation::Tanh.apply(0.0), 0.0);
+        assert!(Activation::Tanh.apply(-10.0) < -0.99);
+        assert_eq!(Activation::Identity.apply(-2.0), -2.0);
+    }
+}
@@ -0,0 +1,71 @@
+//! Dot product used by every neuron, with an optional SIMD version.
+//!
+//! With the `simd` feature, `wide` picks the best instructions available
+//! for the target: SSE/AVX on x86, NEON on ARM and `simd128` on wasm
+//! (when built with `-C target-feature=+simd128`), falling back to
+//! scalar code elsewhere.
+
+#[cfg(not(feature = "simd"))]
+pub(crate) use self::dot_scalar as dot;
+#[cfg(feature = "simd")]
+pub(crate) use self::dot_simd as dot;
+
+pub(crate) fn dot_scalar(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+#[cfg(feature = "simd")]
+pub(crate) fn dot_simd(a: &[f32], b: &[f32]) -> f32 {
+    use wide::f32x8;
+
+    let len = a.len().min(b.len());
+    let (a, b) = (&a[..len], &b[..len]);
+
+    let a_chunks = a.chunks_exact(8);
+    let b_chunks = b.chunks_exact(8);
+    let tail = dot_scalar(a_chunks.remainder(), b_chunks.remainder());
+
+    let sum = a_chunks.zip(b_chunks).fold(f32x8::ZERO, |sum, (a, b)| {
+        let a = f32x8::from(<[f32; 8]>::try_from(a).unwrap());
+        let b = f32x8::from(<[f32; 8]>::try_from(b).unwrap());
+        a.mul_add(b, sum)
+    });
+
+    sum.reduce_add() + tail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalar() {
+        assert_eq!(dot_scalar(&[1.0, 2.0, 3.0], &[4.0, -5.0, 6.0]), 12.0);
+        assert_eq!(dot_scalar(&[], &[]), 0.0);
+    }
+
+    /// The SIMD version adds in a different order, so it may differ from
+    /// the scalar one by a few ulps
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd_matches_scalar() {
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha12Rng;
+
+        let mut rng = ChaCha12Rng::from_seed(Default::default());
+
+        for len in [0, 1, 7, 8, 9, 18, 19, 64, 100] {
+            let a: Vec<f32> = (0..len).map(|_| rng.random_range(-1.0..=1.0)).collect();
+            let b: Vec<f32> = (0..len).map(|_| rng.random_range(-1.0..=1.0)).collect();
+
+            let expected = dot_scalar(&a, &b);
+            let actual = dot_simd(&a, &b);
+            let magnitude: f32 = a.iter().zip(&b).map(|(a, b)| (a * b).abs()).sum();
+
+            assert!(
+                (expected - actual).abs() <= 1e-5 * magnitude.max(1.0),
+                "len {len}: {expected} vs {actual}"
+            );
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use rand::{Rng, RngCore};
+
+use crate::{Activation, dot::dot};
+
+/// A fully connected layer, ReLU activated unless told otherwise.
+///
+/// Weights are kept in a single buffer, one row per neuron, each row
+/// being the neuron's bias followed by one weight per input. That's the
+/// same order `Network::weights` yields them in.
+pub struct Layer {
+    pub(crate) inputs: usize,
+    pub(crate) outputs: usize,
+    pub(crate) activation: Activation,
+    pub(crate) weights: Vec<f32>,
+}
+
+impl Layer {
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    pub fn biases(&self) -> impl Iterator<Item = f32> + '_ {
+        self.rows().map(|row| row[0])
+    }
+
+    /// One row per neuron, with one weight per input
+    pub fn weights(&self) -> impl Iterator<Item = &[f32]> + '_ {
+        self.rows().map(|row| &row[1..])
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[f32]> + '_ {
+        self.weights.chunks_exact(self.inputs + 1)
+    }
+
+    /// Feed `inputs`, made of consecutive rows of `self.inputs` values,
+    /// and write one row of `self.outputs` values per input row into
+    /// `outputs`. `outputs` only grows when it's too small, so reusing it
+    /// across calls doesn't allocate.
+    pub(crate) fn propagate_batch(&self, inputs: &[f32], outputs: &mut Vec<f32>) {
+        assert_eq!(inputs.len() % self.inputs, 0);
+
+        outputs.clear();
+        outputs.reserve(inputs.len() / self.inputs * self.outputs);
+
+        for inputs in inputs.chunks_exact(self.inputs) {
+            outputs.extend(
+                self.rows()
+                    .map(|row| self.activation.apply(row[0] + dot(inputs, &row[1..]))),
+            );
+        }
+    }
+
+    pub(crate) fn random(
+        rng: &mut dyn RngCore,
+        inputs: usize,
+        outputs: usize,
+        activation: Activation,
+    ) -> Self {
+        let weights = (0..(inputs + 1) * outputs)
+            .map(|_| rng.random_range(-1.0..=1.0))
+            .collect();
+
+        Self {
+            inputs,
+            outputs,
+            activation,
+            weights,
+        }
+    }
+
+    pub(crate) fn from_weights(
+        inputs: usize,
+        outputs: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let len = (inputs + 1) * outputs;
+        let weights: Vec<_> = weights.take(len).collect();
+        assert_eq!(weights.len(), len, "got not enough weights");
+
+        Self {
+            inputs,
+            outputs,
+            activation,
+            weights,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    use super::*;
+
+    macro_rules! assert_almost_eq {
+        ($left:expr, $right:expr) => {{
+            let left: f32 = $left;
+            let right: f32 = $right;
+            assert!((left - right).abs() < f32::EPSILON);
+        }};
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = ChaCha12Rng::from_seed(Default::default());
+        let layer = Layer::random(&mut rng, 4, 1, Activation::Relu);
+
+        let expected = [-0.1671462, -0.3439536, -0.8530848, 0.66806483, 0.4668932];
+        layer
+            .weights
+            .iter()
+            .zip(expected)
+            .for_each(|(weight, expected)| assert_almost_eq!(*weight, expected));
+    }
+
+    #[test]
+    fn propagate() {
+        let layer = Layer {
+            inputs: 2,
+            outputs: 1,
+            activation: Activation::Relu,
+            weights: vec![0.5, -0.3, 0.8],
+        };
+        let mut outputs = Vec::new();
+
+        // Ensures .max() (our ReLU) works:
+        layer.propagate_batch(&[-10.0, -10.0], &mut outputs);
+        assert_almost_eq!(outputs[0], 0.0);
+
+        // `0.5` and `1.0` chosen by a fair dice roll
+        layer.propagate_batch(&[0.5, 1.0], &mut outputs);
+        assert_almost_eq!(outputs[0], (-0.3 * 0.5) + (0.8 * 1.0) + 0.5);
+    }
+
+    #[test]
+    fn propagate_batch() {
+        let layer = Layer {
+            inputs: 2,
+            outputs: 2,
+            activation: Activation::Relu,
+            weights: vec![0.5, -0.25, 0.75, 0.0, 1.0, 1.0],
+        };
+        let mut outputs = Vec::new();
+
+        layer.propagate_batch(&[0.5, 1.0, 2.0, 3.0, -1.0, -1.0], &mut outputs);
+        assert_eq!(outputs, vec![1.125, 1.5, 2.25, 5.0, 0.0, 0.0]);
+    }
+}
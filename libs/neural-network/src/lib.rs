@@ -1,9 +1,12 @@
-use std::iter::once;
+use std::mem;
 
 use rand::RngCore;
 
-mod neuron;
-use neuron::Neuron;
+mod activation;
+mod dot;
+mod layer;
+pub use activation::Activation;
+pub use layer::Layer;
 
 // === Network ===
 
@@ -11,28 +14,99 @@ pub struct Network {
     layers: Vec<Layer>,
 }
 
+/// Buffers reused between calls to `propagate_with` and
+/// `propagate_batch`, so running a network doesn't allocate once they've
+/// grown large enough. One scratch can be shared by any number of
+/// networks.
+#[derive(Debug, Default)]
+pub struct Scratch {
+    current: Vec<f32>,
+    next: Vec<f32>,
+}
+
 impl Network {
     pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.layers
-            .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+        self.propagate_with(&inputs, &mut Scratch::default())
+            .to_vec()
+    }
+
+    /// Like `propagate`, but borrows the inputs and returns the outputs
+    /// from `scratch`
+    pub fn propagate_with<'a>(&self, inputs: &[f32], scratch: &'a mut Scratch) -> &'a [f32] {
+        self.assert_single_row(inputs);
+        self.propagate_batch(inputs, scratch)
+    }
+
+    /// Propagate a batch of inputs at once: `inputs` holds consecutive
+    /// rows of one value per input neuron, and the result holds one row
+    /// of outputs per input row, in the same order
+    pub fn propagate_batch<'a>(&self, inputs: &[f32], scratch: &'a mut Scratch) -> &'a [f32] {
+        let Some((first, rest)) = self.layers.split_first() else {
+            scratch.current.clear();
+            scratch.current.extend_from_slice(inputs);
+            return &scratch.current;
+        };
+
+        first.propagate_batch(inputs, &mut scratch.current);
+
+        for layer in rest {
+            layer.propagate_batch(&scratch.current, &mut scratch.next);
+            mem::swap(&mut scratch.current, &mut scratch.next);
+        }
+
+        &scratch.current
+    }
+
+    /// Like `propagate`, but returns the inputs followed by the output of
+    /// every layer
+    pub fn propagate_traced(&self, inputs: Vec<f32>) -> Vec<Vec<f32>> {
+        self.assert_single_row(&inputs);
+
+        let mut trace = Vec::with_capacity(self.layers.len() + 1);
+        trace.push(inputs);
+
+        for layer in &self.layers {
+            let mut outputs = Vec::new();
+            layer.propagate_batch(
+                trace.last().expect("trace starts with the inputs"),
+                &mut outputs,
+            );
+            trace.push(outputs);
+        }
+
+        trace
+    }
+
+    /// A batch of one row looks just like a longer input, which would
+    /// silently run as several rows
+    fn assert_single_row(&self, inputs: &[f32]) {
+        if let Some(first) = self.layers.first() {
+            assert_eq!(inputs.len(), first.inputs, "wrong number of inputs");
+        }
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
     }
 
     pub fn random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
+            .map(|layers| {
+                Layer::random(
+                    rng,
+                    layers[0].neurons,
+                    layers[1].neurons,
+                    layers[1].activation,
+                )
+            })
             .collect();
 
         Self { layers }
     }
 
     pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
-        self.layers
-            .iter()
-            .flat_map(|l| l.neurons.iter())
-            .flat_map(|n| once(&n.bias).chain(&n.weights))
-            .copied()
+        self.layers.iter().flat_map(|l| l.weights.iter()).copied()
     }
 
     pub fn from_weights(layers: &[LayerTopology], weights: impl IntoIterator<Item = f32>) -> Self {
@@ -40,7 +114,7 @@ impl Network {
 
         let layers = layers
             .windows(2)
-            .map(|l| Layer::from_weights(l[0].neurons, l[1].neurons, &mut weights))
+            .map(|l| Layer::from_weights(l[0].neurons, l[1].neurons, l[1].activation, &mut weights))
             .collect();
 
         if weights.next().is_some() {
@@ -51,47 +125,29 @@ impl Network {
     }
 }
 
-// === Layer ===
+// === Topology ===
 
-struct Layer {
-    neurons: Vec<Neuron>,
+pub struct LayerTopology {
+    pub neurons: usize,
+    /// Applied by every neuron of the layer; meaningless for the input
+    /// layer, which only passes its inputs along
+    pub activation: Activation,
 }
 
-impl Layer {
-    fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.neurons
-            .iter()
-            .map(|neuron| neuron.propagate(&inputs))
-            .collect()
-    }
-
-    fn random(rng: &mut dyn RngCore, input_size: usize, output_size: usize) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::random(rng, input_size))
-            .collect();
-
-        Self { neurons }
+impl LayerTopology {
+    /// A layer of ReLU neurons
+    pub fn new(neurons: usize) -> Self {
+        Self {
+            neurons,
+            activation: Activation::Relu,
+        }
     }
 
-    fn from_weights(
-        input_size: usize,
-        output_size: usize,
-        weights: &mut dyn Iterator<Item = f32>,
-    ) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::from_weights(input_size, weights))
-            .collect();
-
-        Self { neurons }
+    pub fn with_activation(self, activation: Activation) -> Self {
+        Self { activation, ..self }
     }
 }
 
-// === Topology ===
-
-pub struct LayerTopology {
-    pub neurons: usize,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,16 +157,16 @@ mod tests {
         let network = Network {
             layers: vec![
                 Layer {
-                    neurons: vec![Neuron {
-                        bias: 0.1,
-                        weights: vec![0.1, 0.2, 0.3],
-                    }],
+                    inputs: 3,
+                    outputs: 1,
+                    activation: Activation::Relu,
+                    weights: vec![0.1, 0.1, 0.2, 0.3],
                 },
                 Layer {
-                    neurons: vec![Neuron {
-                        bias: 0.5,
-                        weights: vec![0.6, 0.7, 0.8],
-                    }],
+                    inputs: 3,
+                    outputs: 1,
+                    activation: Activation::Relu,
+                    weights: vec![0.5, 0.6, 0.7, 0.8],
                 },
             ],
         };
@@ -123,7 +179,7 @@ mod tests {
 
     #[test]
     fn from_weights() {
-        let layers = &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 2 }];
+        let layers = &[LayerTopology::new(3), LayerTopology::new(2)];
 
         let weights = vec![0.1, 0.1, 0.2, 0.3, 0.5, 0.6, 0.7, 0.8];
         let network = Network::from_weights(layers, weights.clone());
@@ -131,4 +187,92 @@ mod tests {
 
         assert_eq!(actual, weights);
     }
+
+    #[test]
+    fn layers() {
+        let layers = &[LayerTopology::new(3), LayerTopology::new(2)];
+        let weights = vec![0.1, 0.1, 0.2, 0.3, 0.5, 0.6, 0.7, 0.8];
+        let network = Network::from_weights(layers, weights);
+
+        let layer = &network.layers()[0];
+        assert_eq!((layer.inputs(), layer.outputs()), (3, 2));
+        assert_eq!(layer.biases().collect::<Vec<_>>(), vec![0.1, 0.5]);
+        assert_eq!(
+            layer.weights().collect::<Vec<_>>(),
+            vec![&[0.1, 0.2, 0.3][..], &[0.6, 0.7, 0.8][..]]
+        );
+    }
+
+    #[test]
+    fn propagate_traced() {
+        let layers = &[
+            LayerTopology::new(2),
+            LayerTopology::new(1),
+            LayerTopology::new(1),
+        ];
+        let weights = vec![0.5, 1.0, -1.0, 0.0, 2.0];
+        let network = Network::from_weights(layers, weights);
+
+        let trace = network.propagate_traced(vec![2.0, 1.0]);
+
+        assert_eq!(trace, vec![vec![2.0, 1.0], vec![1.5], vec![3.0]]);
+        assert_eq!(network.propagate(vec![2.0, 1.0]), vec![3.0]);
+    }
+
+    #[test]
+    fn activations() {
+        let layers = &[
+            LayerTopology::new(1),
+            LayerTopology::new(1).with_activation(Activation::Identity),
+            LayerTopology::new(1).with_activation(Activation::Sigmoid),
+        ];
+        let network = Network::from_weights(layers, vec![0.0, 1.0, 0.0, 0.0]);
+
+        // ReLU would have stopped the negative input at the first layer
+        assert_eq!(network.layers()[0].activation(), Activation::Identity);
+        assert_eq!(network.propagate_traced(vec![-2.0])[1], vec![-2.0]);
+        assert_eq!(network.propagate(vec![-2.0]), vec![0.5]);
+    }
+
+    #[test]
+    fn propagate_batch() {
+        let layers = &[
+            LayerTopology::new(2),
+            LayerTopology::new(3),
+            LayerTopology::new(2),
+        ];
+        let weights = (0..17).map(|i| (i as f32 - 8.0) / 10.0);
+        let network = Network::from_weights(layers, weights);
+
+        let batch = [0.2, 0.9, 1.0, 0.0, 0.5, 0.5];
+        let mut scratch = Scratch::default();
+        let outputs = network.propagate_batch(&batch, &mut scratch).to_vec();
+
+        let expected: Vec<_> = batch
+            .chunks(2)
+            .flat_map(|inputs| network.propagate(inputs.to_vec()))
+            .collect();
+        assert_eq!(outputs, expected);
+
+        // The scratch buffers are big enough now, so they're reused as-is;
+        // they may swap places between layers though
+        let capacities = |scratch: &Scratch| {
+            let mut capacities = [scratch.current.capacity(), scratch.next.capacity()];
+            capacities.sort();
+            capacities
+        };
+
+        let before = capacities(&scratch);
+        network.propagate_with(&batch[..2], &mut scratch);
+        assert_eq!(capacities(&scratch), before);
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong number of inputs")]
+    fn propagate_rejects_a_batch() {
+        let layers = &[LayerTopology::new(2), LayerTopology::new(1)];
+        let network = Network::from_weights(layers, vec![0.0; 3]);
+
+        network.propagate(vec![0.2, 0.9, 1.0, 0.0]);
+    }
 }
@@ -1,48 +1,639 @@
+pub mod protocol;
+
+use js_sys::Float32Array;
 use lib_simulation as sim;
 use rand::{rng, rngs::ThreadRng};
-use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::prelude::{JsError, wasm_bindgen};
 
 // === Simulation ===
 #[wasm_bindgen]
 pub struct Simulation {
     rng: ThreadRng,
     sim: sim::Simulation,
+    /// Backs `animals_view` and `foods_view`
+    snapshot: protocol::Snapshot,
 }
 
 #[wasm_bindgen]
 impl Simulation {
+    /// Throws if `config` holds invalid values
     #[wasm_bindgen(constructor)]
-    pub fn new(animals_count: usize, food_count: usize) -> Self {
+    pub fn new(
+        animals_count: usize,
+        food_count: usize,
+        config: Option<Config>,
+    ) -> Result<Simulation, JsError> {
         let mut rng = rng();
-        let sim = sim::Simulation::random(&mut rng, animals_count, food_count);
+        let config = config.map(sim::Config::from).unwrap_or_default();
+        let sim = sim::Simulation::random_with_config(&mut rng, config, animals_count, food_count)?;
+
+        Ok(Self::from_sim(rng, sim))
+    }
+
+    /// Restore a population exported with `export_population`, or a
+    /// checkpoint written by the CLI
+    pub fn import_population(json: &str) -> Result<Simulation, JsError> {
+        let checkpoint = sim::Checkpoint::from_json(json)?;
+        let (sim, _) = sim::Simulation::from_checkpoint(checkpoint);
 
-        Self { rng, sim }
+        Ok(Self::from_sim(rng(), sim))
+    }
+
+    /// The birds, foods, settings and hall of fame as JSON, in the same
+    /// format the CLI reads with `--resume`
+    pub fn export_population(&self) -> String {
+        self.sim.checkpoint(None).to_json()
     }
 
     pub fn world(&self) -> World {
         World::from(self.sim.world())
     }
 
+    /// `x, y, rotation, energy` of every bird, back to back.
+    ///
+    /// This is a view straight into wasm memory, updated in place by
+    /// `step` and `train`; it's invalidated when wasm memory grows, so
+    /// get a fresh one every frame instead of keeping it around.
+    pub fn animals_view(&self) -> Float32Array {
+        // SAFETY: the view is handed to JS right away, with no Rust
+        // allocation in between that could grow the memory under it
+        unsafe { Float32Array::view(self.snapshot.animals.as_flattened()) }
+    }
+
+    /// `x, y, kind, available` of every food, back to back, where
+    /// `available` is 1 for the foods birds can eat right now and 0 for
+    /// the rest; indices match the ones of events. See `animals_view`
+    pub fn foods_view(&self) -> Float32Array {
+        // SAFETY: see `animals_view`
+        unsafe { Float32Array::view(self.snapshot.foods.as_flattened()) }
+    }
+
     pub fn step(&mut self) -> Option<Data> {
-        match self.sim.step(&mut self.rng) {
-            Some((stats, info)) => Some(Data {
-                stats: Stats::from(stats),
-                info: Information::from(info),
-            }),
-            None => None,
+        let data = self.sim.step(&mut self.rng).map(Data::from);
+
+        self.snapshot.update(&self.sim);
+        data
+    }
+
+    /// Advance up to `n` steps, returning the summary of every generation
+    /// that ended along the way
+    pub fn step_n(&mut self, n: usize) -> Vec<Data> {
+        let summaries = (0..n)
+            .filter_map(|_| self.sim.step(&mut self.rng))
+            .map(Data::from)
+            .collect();
+
+        self.snapshot.update(&self.sim);
+        summaries
+    }
+
+    /// Keep stepping for about `ms` milliseconds, returning the summary of
+    /// every generation that ended along the way
+    pub fn advance_for(&mut self, ms: f64) -> Vec<Data> {
+        // Reading the clock crosses into JS, so it's only checked every
+        // few steps
+        const STEPS_PER_CHECK: usize = 16;
+
+        let deadline = js_sys::Date::now() + ms;
+        let mut summaries = Vec::new();
+
+        while js_sys::Date::now() < deadline {
+            summaries.extend(
+                (0..STEPS_PER_CHECK)
+                    .filter_map(|_| self.sim.step(&mut self.rng))
+                    .map(Data::from),
+            );
         }
+
+        self.snapshot.update(&self.sim);
+        summaries
+    }
+
+    /// What the bird at `index` currently sees and decides
+    pub fn inspect(&self, index: usize) -> Option<AnimalDetails> {
+        let animal = self.sim.world().animals().get(index)?;
+        let vision = self.sim.brain_inputs(index)?;
+        let outputs = animal.brain_outputs(vision.clone());
+
+        Some(AnimalDetails {
+            satiation: animal.satiation(),
+            poisoned: animal.poisoned(),
+            speed: animal.speed(),
+            energy: animal.energy(),
+            survived: animal.survived(),
+            vision,
+            outputs,
+            output_names: self
+                .sim
+                .config()
+                .brain_outputs
+                .names()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        })
+    }
+
+    /// Weights of the brain of the bird at `index`, with the activations
+    /// for what it currently sees
+    pub fn network(&self, index: usize) -> Option<NetworkDetails> {
+        let network = self.sim.world().animals().get(index)?.brain();
+        let mut trace = network.propagate_traced(self.sim.brain_inputs(index)?);
+
+        let activations = trace.split_off(1);
+        let layers = network
+            .layers()
+            .iter()
+            .zip(activations)
+            .map(|(layer, activations)| NetworkLayer {
+                inputs: layer.inputs(),
+                outputs: layer.outputs(),
+                weights: layer.weights().flatten().copied().collect(),
+                biases: layer.biases().collect(),
+                activations,
+            })
+            .collect();
+
+        Some(NetworkDetails {
+            inputs: trace.remove(0),
+            layers,
+        })
+    }
+
+    /// The best birds seen so far, best first
+    pub fn hall_of_fame(&self) -> Vec<HallOfFameEntry> {
+        self.sim
+            .hall_of_fame()
+            .entries()
+            .iter()
+            .map(HallOfFameEntry::from)
+            .collect()
+    }
+
+    /// The hall of fame as JSON, in the same format the native
+    /// `HallOfFame::save` writes
+    pub fn hall_of_fame_json(&self) -> String {
+        serde_json::to_string(self.sim.hall_of_fame()).expect("hall of fame is serializable")
+    }
+
+    /// Record a replay of every generation from now on
+    pub fn record_replays(&mut self, enabled: bool) {
+        self.sim.record_replays(enabled);
+    }
+
+    /// Start or stop collecting events, to be taken with `drain_events`
+    pub fn record_events(&mut self, enabled: bool) {
+        self.sim.record_events(enabled);
+    }
+
+    /// Events that happened since the last call, oldest first
+    pub fn drain_events(&mut self) -> Vec<SimulationEvent> {
+        self.sim.drain_events().map(SimulationEvent::from).collect()
+    }
+
+    /// Replay file of the last finished generation, if it was recorded
+    pub fn last_replay(&self) -> Option<Vec<u8>> {
+        self.sim.last_replay().map(sim::Replay::to_bytes)
     }
 
     pub fn train(&mut self) -> Data {
-        let (statas, info) = self.sim.train(&mut self.rng);
+        let summary = self.sim.train(&mut self.rng);
+        self.snapshot.update(&self.sim);
 
-        Data {
-            stats: Stats::from(statas),
-            info: Information::from(info),
+        Data::from(summary)
+    }
+}
+
+impl Simulation {
+    fn from_sim(rng: ThreadRng, sim: sim::Simulation) -> Self {
+        let snapshot = protocol::Snapshot::new(&sim);
+
+        Self { rng, sim, snapshot }
+    }
+}
+
+// === Config ===
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub generation_length: usize,
+
+    /// Evolve bird by bird instead of a generation at a time
+    pub steady_state: bool,
+    /// Only used with `steady_state`
+    pub foods_to_reproduce: usize,
+
+    pub speed_min: f32,
+    pub speed_max: f32,
+    pub speed_accel: f32,
+    pub rotation_accel: f32,
+
+    /// Energy a bird starts with, and the most it can store
+    pub energy_max: f32,
+    pub energy_per_food: f32,
+    /// Energy spent every step; with no costs birds never starve
+    pub energy_idle_cost: f32,
+    /// Energy spent every step per unit of speed
+    pub energy_speed_cost: f32,
+    /// Fitness for surviving the whole generation, on top of one per food
+    pub fitness_survival: f32,
+
+    /// Foods grow around this many drifting patches; 0 spreads them
+    /// uniformly
+    pub food_patches: usize,
+    pub food_patch_radius: f32,
+    pub food_patch_drift: f32,
+    /// Steps an eaten food takes to grow back
+    pub food_regrowth: usize,
+    /// Eaten foods that grow back every generation, unlimited if unset
+    pub food_budget: Option<usize>,
+    /// Steps of a cycle of seasons, 0 disables them
+    pub food_season_length: usize,
+    pub food_season_amplitude: f32,
+    /// Share of foods that are poisonous, draining as much energy as
+    /// normal foods restore; 0 makes every food the same
+    pub poison_share: f32,
+
+    pub eye_fov_range: f32,
+    pub eye_fov_angle: f32,
+    pub eye_cells: usize,
+    /// Let birds see each other, so they can evolve flocking
+    pub eye_birds: bool,
+    pub mutation_chance: f32,
+    pub mutation_coeff: f32,
+    pub selection: Selection,
+
+    /// Only used with `Selection.Tournament`
+    pub tournament_size: usize,
+
+    /// Also feed the brain its speed, see `sim::BrainInputs`
+    pub speed_input: bool,
+    /// Also feed the brain its heading
+    pub compass_input: bool,
+    /// Also feed the brain its energy
+    pub energy_input: bool,
+    /// Also feed the brain the steps since it last ate
+    pub hunger_input: bool,
+    /// Also feed the brain how far into the generation it is
+    pub clock_input: bool,
+    /// Let the brain decide when to eat
+    pub eat_output: bool,
+    /// Let the brain fly `boost_factor` times faster for more energy
+    pub boost_output: bool,
+    pub boost_factor: f32,
+    /// Let the brain emit a signal other birds see; needs `eye_birds`
+    pub signal_output: bool,
+
+    /// Hidden layers of the brain; empty wires the eye straight into the
+    /// outputs
+    #[wasm_bindgen(getter_with_clone)]
+    pub hidden_layers: Vec<HiddenLayer>,
+
+    /// How many of the best birds ever seen are kept
+    pub hall_of_fame_size: usize,
+}
+
+#[wasm_bindgen]
+impl Config {
+    /// The settings the simulation uses by default
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::from(sim::Config::default())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<sim::Config> for Config {
+    fn from(config: sim::Config) -> Self {
+        let (selection, tournament_size) = match config.selection {
+            sim::Selection::RouletteWheel => (Selection::RouletteWheel, 2),
+            sim::Selection::Tournament { size } => (Selection::Tournament, size),
+        };
+
+        let (steady_state, foods_to_reproduce) = match config.evolution {
+            sim::Evolution::Generational => (false, 5),
+            sim::Evolution::SteadyState { foods_to_reproduce } => (true, foods_to_reproduce),
+        };
+
+        let share = |poisonous: bool| -> f32 {
+            config
+                .food_kinds
+                .iter()
+                .filter(|kind| kind.poisonous == poisonous)
+                .map(|kind| kind.share)
+                .sum()
+        };
+        let poison_share = share(true) / (share(true) + share(false));
+
+        Self {
+            generation_length: config.generation_length,
+            steady_state,
+            foods_to_reproduce,
+            speed_min: config.speed_min,
+            speed_max: config.speed_max,
+            speed_accel: config.speed_accel,
+            rotation_accel: config.rotation_accel,
+            energy_max: config.energy_max,
+            energy_per_food: config.energy_per_food,
+            energy_idle_cost: config.energy_idle_cost,
+            energy_speed_cost: config.energy_speed_cost,
+            fitness_survival: config.fitness_survival,
+            food_patches: config.food_patches,
+            food_patch_radius: config.food_patch_radius,
+            food_patch_drift: config.food_patch_drift,
+            food_regrowth: config.food_regrowth,
+            food_budget: config.food_budget,
+            food_season_length: config.food_season_length,
+            food_season_amplitude: config.food_season_amplitude,
+            poison_share,
+            eye_fov_range: config.eye_fov_range,
+            eye_fov_angle: config.eye_fov_angle,
+            eye_cells: config.eye_cells,
+            eye_birds: config.eye_birds,
+            mutation_chance: config.mutation_chance,
+            mutation_coeff: config.mutation_coeff,
+            selection,
+            tournament_size,
+            speed_input: config.brain_inputs.speed,
+            compass_input: config.brain_inputs.compass,
+            energy_input: config.brain_inputs.energy,
+            hunger_input: config.brain_inputs.hunger,
+            clock_input: config.brain_inputs.clock,
+            eat_output: config.brain_outputs.eat,
+            boost_output: config.brain_outputs.boost,
+            boost_factor: config.boost_factor,
+            signal_output: config.brain_outputs.signal,
+            hidden_layers: config
+                .brain_hidden_layers
+                .iter()
+                .map(HiddenLayer::from)
+                .collect(),
+            hall_of_fame_size: config.hall_of_fame_size,
+        }
+    }
+}
+
+impl From<Config> for sim::Config {
+    fn from(config: Config) -> Self {
+        let selection = match config.selection {
+            Selection::RouletteWheel => sim::Selection::RouletteWheel,
+            Selection::Tournament => sim::Selection::Tournament {
+                size: config.tournament_size,
+            },
+        };
+
+        let evolution = if config.steady_state {
+            sim::Evolution::SteadyState {
+                foods_to_reproduce: config.foods_to_reproduce,
+            }
+        } else {
+            sim::Evolution::Generational
+        };
+
+        let mut food_kinds = vec![sim::FoodKind {
+            share: 1.0 - config.poison_share,
+            ..sim::FoodKind::NORMAL
+        }];
+        if config.poison_share > 0.0 {
+            food_kinds.push(sim::FoodKind {
+                share: config.poison_share,
+                nutrition: -1.0,
+                poisonous: true,
+            });
+        }
+
+        Self {
+            generation_length: config.generation_length,
+            evolution,
+            speed_min: config.speed_min,
+            speed_max: config.speed_max,
+            speed_accel: config.speed_accel,
+            rotation_accel: config.rotation_accel,
+            boost_factor: config.boost_factor,
+            energy_max: config.energy_max,
+            energy_per_food: config.energy_per_food,
+            energy_idle_cost: config.energy_idle_cost,
+            energy_speed_cost: config.energy_speed_cost,
+            fitness_survival: config.fitness_survival,
+            food_kinds,
+            food_patches: config.food_patches,
+            food_patch_radius: config.food_patch_radius,
+            food_patch_drift: config.food_patch_drift,
+            food_regrowth: config.food_regrowth,
+            food_budget: config.food_budget,
+            food_season_length: config.food_season_length,
+            food_season_amplitude: config.food_season_amplitude,
+            eye_fov_range: config.eye_fov_range,
+            eye_fov_angle: config.eye_fov_angle,
+            eye_cells: config.eye_cells,
+            eye_birds: config.eye_birds,
+            mutation_chance: config.mutation_chance,
+            mutation_coeff: config.mutation_coeff,
+            selection,
+            brain_inputs: sim::BrainInputs {
+                speed: config.speed_input,
+                compass: config.compass_input,
+                energy: config.energy_input,
+                hunger: config.hunger_input,
+                clock: config.clock_input,
+            },
+            brain_outputs: sim::BrainOutputs {
+                eat: config.eat_output,
+                boost: config.boost_output,
+                signal: config.signal_output,
+            },
+            brain_hidden_layers: config
+                .hidden_layers
+                .iter()
+                .map(|&layer| sim::HiddenLayer::from(layer))
+                .collect(),
+            hall_of_fame_size: config.hall_of_fame_size,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selection {
+    RouletteWheel,
+    Tournament,
+}
+
+/// A hidden layer of the brain, see `Config.hidden_layers`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HiddenLayer {
+    pub neurons: usize,
+    pub activation: Activation,
+}
+
+#[wasm_bindgen]
+impl HiddenLayer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(neurons: usize, activation: Activation) -> Self {
+        Self {
+            neurons,
+            activation,
+        }
+    }
+}
+
+impl From<&sim::HiddenLayer> for HiddenLayer {
+    fn from(layer: &sim::HiddenLayer) -> Self {
+        Self::new(layer.neurons, layer.activation.into())
+    }
+}
+
+impl From<HiddenLayer> for sim::HiddenLayer {
+    fn from(layer: HiddenLayer) -> Self {
+        Self {
+            neurons: layer.neurons,
+            activation: layer.activation.into(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Activation {
+    Relu,
+    Sigmoid,
+    Tanh,
+    Identity,
+}
+
+impl From<sim::Activation> for Activation {
+    fn from(activation: sim::Activation) -> Self {
+        match activation {
+            sim::Activation::Relu => Activation::Relu,
+            sim::Activation::Sigmoid => Activation::Sigmoid,
+            sim::Activation::Tanh => Activation::Tanh,
+            sim::Activation::Identity => Activation::Identity,
         }
     }
 }
 
+impl From<Activation> for sim::Activation {
+    fn from(activation: Activation) -> Self {
+        match activation {
+            Activation::Relu => sim::Activation::Relu,
+            Activation::Sigmoid => sim::Activation::Sigmoid,
+            Activation::Tanh => sim::Activation::Tanh,
+            Activation::Identity => sim::Activation::Identity,
+        }
+    }
+}
+
+// === WorkerHost ===
+/// Entry point for running a simulation inside a Web Worker: messages
+/// are the binary commands and event batches described in `protocol`
+#[wasm_bindgen]
+pub struct WorkerHost {
+    host: protocol::Host<ThreadRng>,
+}
+
+#[wasm_bindgen]
+impl WorkerHost {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        animals_count: usize,
+        food_count: usize,
+        config: Option<Config>,
+    ) -> Result<WorkerHost, JsError> {
+        let mut rng = rng();
+        let config = config.map(sim::Config::from).unwrap_or_default();
+        let sim = sim::Simulation::random_with_config(&mut rng, config, animals_count, food_count)?;
+
+        Ok(Self {
+            host: protocol::Host::new(sim, rng),
+        })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.host.is_running()
+    }
+
+    /// Handle an encoded command, returning an encoded batch of events
+    pub fn handle(&mut self, message: &[u8]) -> Result<Vec<u8>, JsError> {
+        let command = protocol::Command::decode(message)?;
+        let events = self.host.handle(command)?;
+
+        Ok(protocol::Event::encode_batch(&events))
+    }
+
+    /// Advance the simulation, returning an encoded batch of events
+    pub fn tick(&mut self) -> Vec<u8> {
+        protocol::Event::encode_batch(&self.host.tick())
+    }
+}
+
+// === ReplayPlayer ===
+#[wasm_bindgen]
+pub struct ReplayPlayer {
+    player: sim::ReplayPlayer,
+}
+
+#[wasm_bindgen]
+impl ReplayPlayer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<ReplayPlayer, JsError> {
+        let replay = sim::Replay::from_bytes(bytes)?;
+
+        Ok(Self {
+            player: sim::ReplayPlayer::new(replay),
+        })
+    }
+
+    pub fn generation(&self) -> usize {
+        self.player.replay().generation()
+    }
+
+    pub fn frames(&self) -> usize {
+        self.player.replay().frames().len()
+    }
+
+    pub fn rewind(&mut self) {
+        self.player.rewind();
+    }
+
+    /// The world after the next step, or nothing once the replay is over
+    pub fn next_frame(&mut self) -> Option<World> {
+        let (animals, foods) = self.player.next_frame()?;
+
+        let foods = foods
+            .iter()
+            .map(|food| Food {
+                x: food.position.x,
+                y: food.position.y,
+                kind: food.kind,
+                available: food.available,
+            })
+            .collect();
+
+        let animals = animals.to_vec();
+        let animals = animals
+            .iter()
+            .enumerate()
+            .map(|(i, animal)| Animal {
+                x: animal.position.x,
+                y: animal.position.y,
+                rotation: animal.rotation,
+                speed: animal.speed,
+                energy: animal.energy,
+                satiation: self.player.satiation(i),
+                poisoned: self.player.poisoned(i),
+            })
+            .collect();
+
+        Some(World { animals, foods })
+    }
+}
+
 // === World ===
 #[wasm_bindgen]
 #[derive(Clone, Debug)]
@@ -70,6 +661,11 @@ pub struct Animal {
     pub x: f32,
     pub y: f32,
     pub rotation: f32,
+    pub speed: f32,
+    /// Dead birds have none left
+    pub energy: f32,
+    pub satiation: usize,
+    pub poisoned: usize,
 }
 
 impl From<&sim::Animal> for Animal {
@@ -78,16 +674,48 @@ impl From<&sim::Animal> for Animal {
             x: animal.position().x,
             y: animal.position().y,
             rotation: animal.rotation().angle(),
+            speed: animal.speed(),
+            energy: animal.energy(),
+            satiation: animal.satiation(),
+            poisoned: animal.poisoned(),
         }
     }
 }
 
+// === AnimalDetails ===
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct AnimalDetails {
+    pub satiation: usize,
+    pub poisoned: usize,
+    pub speed: f32,
+    pub energy: f32,
+    /// Steps survived in the current generation
+    pub survived: usize,
+
+    /// Inputs of the brain: every eye cell, then the extra inputs
+    #[wasm_bindgen(getter_with_clone)]
+    pub vision: Vec<f32>,
+
+    /// Raw outputs of the brain; the first two drive speed and rotation
+    #[wasm_bindgen(getter_with_clone)]
+    pub outputs: Vec<f32>,
+
+    /// What every output drives, see `outputs`
+    #[wasm_bindgen(getter_with_clone)]
+    pub output_names: Vec<String>,
+}
+
 // === Food ===
 #[wasm_bindgen]
 #[derive(Clone, Debug)]
 pub struct Food {
     pub x: f32,
     pub y: f32,
+    /// Index of its kind; with `Config.poison_share` set, 1 is poisonous
+    pub kind: usize,
+    /// Whether birds can see and eat it right now, see `sim::Food`
+    pub available: bool,
 }
 
 impl From<&sim::Food> for Food {
@@ -95,6 +723,8 @@ impl From<&sim::Food> for Food {
         Self {
             x: food.position().x,
             y: food.position().y,
+            kind: food.kind(),
+            available: food.is_available(),
         }
     }
 }
@@ -123,12 +753,145 @@ impl From<sim::Statistics> for Stats {
 #[derive(Clone, Debug, Copy)]
 pub struct Information {
     pub generation: usize,
+    /// Average distance from every bird to the closest other one
+    pub cohesion: f32,
 }
 
 impl From<sim::Information> for Information {
     fn from(info: sim::Information) -> Self {
         Self {
             generation: info.generation(),
+            cohesion: info.cohesion(),
+        }
+    }
+}
+
+// === SimulationEvent ===
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    FoodEaten,
+    FoodRespawned,
+    AnimalBorn,
+    AnimalDied,
+    GenerationEnded,
+}
+
+/// A `sim::Event` flattened for JS; only the fields that make sense for
+/// its `kind` are set
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct SimulationEvent {
+    pub kind: EventKind,
+    pub animal: Option<usize>,
+    pub food: Option<usize>,
+    pub parent: Option<usize>,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+    pub generation: Option<usize>,
+    pub stats: Option<Stats>,
+}
+
+impl SimulationEvent {
+    fn new(kind: EventKind) -> Self {
+        Self {
+            kind,
+            animal: None,
+            food: None,
+            parent: None,
+            x: None,
+            y: None,
+            generation: None,
+            stats: None,
+        }
+    }
+}
+
+impl From<sim::Event> for SimulationEvent {
+    fn from(event: sim::Event) -> Self {
+        match event {
+            sim::Event::FoodEaten {
+                animal,
+                food,
+                position,
+            } => Self {
+                animal: Some(animal),
+                food: Some(food),
+                x: Some(position.x),
+                y: Some(position.y),
+                ..Self::new(EventKind::FoodEaten)
+            },
+            sim::Event::FoodRespawned { food, position } => Self {
+                food: Some(food),
+                x: Some(position.x),
+                y: Some(position.y),
+                ..Self::new(EventKind::FoodRespawned)
+            },
+            sim::Event::AnimalBorn { animal, parent } => Self {
+                animal: Some(animal),
+                parent: Some(parent),
+                ..Self::new(EventKind::AnimalBorn)
+            },
+            sim::Event::AnimalDied { animal } => Self {
+                animal: Some(animal),
+                ..Self::new(EventKind::AnimalDied)
+            },
+            sim::Event::GenerationEnded { generation, stats } => Self {
+                generation: Some(generation),
+                stats: Some(Stats::from(stats)),
+                ..Self::new(EventKind::GenerationEnded)
+            },
+        }
+    }
+}
+
+// === NetworkDetails ===
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct NetworkDetails {
+    /// Values fed into the first layer
+    #[wasm_bindgen(getter_with_clone)]
+    pub inputs: Vec<f32>,
+
+    #[wasm_bindgen(getter_with_clone)]
+    pub layers: Vec<NetworkLayer>,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct NetworkLayer {
+    pub inputs: usize,
+    pub outputs: usize,
+
+    /// Row-major, `outputs` rows of `inputs` weights each
+    #[wasm_bindgen(getter_with_clone)]
+    pub weights: Vec<f32>,
+
+    #[wasm_bindgen(getter_with_clone)]
+    pub biases: Vec<f32>,
+
+    /// Output of every neuron for the current inputs
+    #[wasm_bindgen(getter_with_clone)]
+    pub activations: Vec<f32>,
+}
+
+// === HallOfFameEntry ===
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct HallOfFameEntry {
+    pub fitness: f32,
+    pub generation: usize,
+
+    #[wasm_bindgen(getter_with_clone)]
+    pub genes: Vec<f32>,
+}
+
+impl From<&sim::HallOfFameEntry> for HallOfFameEntry {
+    fn from(entry: &sim::HallOfFameEntry) -> Self {
+        Self {
+            fitness: entry.fitness,
+            generation: entry.generation,
+            genes: entry.chromosome.genes.clone(),
         }
     }
 }
@@ -140,3 +903,43 @@ pub struct Data {
     pub stats: Stats,
     pub info: Information,
 }
+
+impl From<(sim::Statistics, sim::Information)> for Data {
+    fn from((stats, info): (sim::Statistics, sim::Information)) -> Self {
+        Self {
+            stats: Stats::from(stats),
+            info: Information::from(info),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_roundtrip() {
+        let config = sim::Config {
+            energy_idle_cost: 0.0002,
+            energy_speed_cost: 0.1,
+            fitness_survival: 1.0,
+            boost_factor: 3.0,
+            hall_of_fame_size: 4,
+            brain_inputs: sim::BrainInputs {
+                compass: true,
+                clock: true,
+                ..sim::BrainInputs::default()
+            },
+            brain_hidden_layers: vec![
+                sim::HiddenLayer {
+                    neurons: 18,
+                    activation: sim::Activation::Tanh,
+                },
+                sim::HiddenLayer::new(9),
+            ],
+            ..sim::Config::default()
+        };
+
+        assert_eq!(sim::Config::from(Config::from(config.clone())), config);
+    }
+}
@@ -0,0 +1,534 @@
+//! Messages between the UI and a simulation running in a Web Worker.
+//!
+//! This is plain Rust so it can be tested natively; `WorkerHost` only
+//! wraps `Host` for wasm-bindgen. Every message is little endian and
+//! starts with the `VERSION` byte, so a stale worker bundle fails loudly
+//! instead of misreading it; then commands have a one byte tag:
+//!
+//! ```text
+//! commands (UI -> worker)
+//!   0 start
+//!   1 pause
+//!   2 set speed      steps per tick: u32, capped at `MAX_SPEED`
+//!   3 snapshot
+//!   4 export population
+//!   5 import population  len: u32, then that many bytes of JSON
+//!
+//! events (worker -> UI), sent as a batch: count: u32, then the events
+//!   0 snapshot    generation: u32
+//!                 animals: u32, then (x, y, rotation, energy: f32) each
+//!                 foods: u32, then (x, y, kind, available: f32) each,
+//!                 available ones included so indices match `sim::Event`
+//!   1 generation  generation: u32, min, max, avg, cohesion: f32
+//!   2 population  len: u32, then that many bytes of JSON
+//! ```
+
+use std::io::{self, Read, Write};
+
+use lib_simulation as sim;
+use rand::RngCore;
+
+/// Bumped whenever the layout of a message changes
+pub const VERSION: u8 = 1;
+
+/// Most steps a single `Host::tick` runs, so one message can't keep the
+/// worker busy for minutes
+pub const MAX_SPEED: u32 = 10_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Start,
+    Pause,
+    /// How many steps every `Host::tick` runs
+    SetSpeed(u32),
+    Snapshot,
+    ExportPopulation,
+    /// Replace the simulation with an exported population
+    ImportPopulation(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Snapshot(Snapshot),
+    Generation {
+        generation: u32,
+        min: f32,
+        max: f32,
+        avg: f32,
+        cohesion: f32,
+    },
+    Population(String),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    pub generation: u32,
+    /// x, y, rotation and energy of every bird
+    pub animals: Vec<[f32; 4]>,
+    /// x, y, kind and whether it's available (1) or not (0) of every
+    /// food, in the same order as in the world
+    pub foods: Vec<[f32; 4]>,
+}
+
+impl Snapshot {
+    pub fn new(simulation: &sim::Simulation) -> Self {
+        let mut snapshot = Self::default();
+        snapshot.update(simulation);
+        snapshot
+    }
+
+    /// Overwrite it with the current state of `simulation`; the buffers
+    /// only allocate when the world grows
+    pub fn update(&mut self, simulation: &sim::Simulation) {
+        let world = simulation.world();
+        self.generation = simulation.generation() as u32;
+
+        self.animals.clear();
+        self.animals.extend(world.animals().iter().map(|animal| {
+            [
+                animal.position().x,
+                animal.position().y,
+                animal.rotation().angle(),
+                animal.energy(),
+            ]
+        }));
+
+        self.foods.clear();
+        self.foods.extend(world.foods().iter().map(|food| {
+            [
+                food.position().x,
+                food.position().y,
+                food.kind() as f32,
+                if food.is_available() { 1.0 } else { 0.0 },
+            ]
+        }));
+    }
+}
+
+impl Command {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![VERSION];
+        let w = &mut bytes;
+
+        match self {
+            Command::Start => w.push(0),
+            Command::Pause => w.push(1),
+            Command::SetSpeed(speed) => {
+                w.push(2);
+                write_u32(w, *speed);
+            }
+            Command::Snapshot => w.push(3),
+            Command::ExportPopulation => w.push(4),
+            Command::ImportPopulation(json) => {
+                w.push(5);
+                write_str(w, json);
+            }
+        }
+
+        bytes
+    }
+
+    pub fn decode(mut bytes: &[u8]) -> io::Result<Self> {
+        let r = &mut bytes;
+        read_version(r)?;
+
+        let command = match read_u8(r)? {
+            0 => Command::Start,
+            1 => Command::Pause,
+            2 => Command::SetSpeed(read_u32(r)?),
+            3 => Command::Snapshot,
+            4 => Command::ExportPopulation,
+            5 => Command::ImportPopulation(read_str(r)?),
+            _ => return Err(invalid_data("unknown command")),
+        };
+
+        if !r.is_empty() {
+            return Err(invalid_data("trailing bytes after command"));
+        }
+
+        Ok(command)
+    }
+}
+
+impl Event {
+    pub fn encode_batch(events: &[Event]) -> Vec<u8> {
+        let mut bytes = vec![VERSION];
+        let w = &mut bytes;
+
+        write_u32(w, events.len() as u32);
+        for event in events {
+            event.write_to(w);
+        }
+
+        bytes
+    }
+
+    pub fn decode_batch(mut bytes: &[u8]) -> io::Result<Vec<Self>> {
+        let r = &mut bytes;
+        read_version(r)?;
+
+        let count = read_u32(r)?;
+        let mut events = Vec::new();
+        for _ in 0..count {
+            events.push(Self::read_from(r)?);
+        }
+
+        if !r.is_empty() {
+            return Err(invalid_data("trailing bytes after events"));
+        }
+
+        Ok(events)
+    }
+
+    fn write_to(&self, w: &mut Vec<u8>) {
+        match self {
+            Event::Snapshot(snapshot) => {
+                w.push(0);
+                write_u32(w, snapshot.generation);
+
+                write_u32(w, snapshot.animals.len() as u32);
+                for animal in &snapshot.animals {
+                    animal.iter().for_each(|&value| write_f32(w, value));
+                }
+
+                write_u32(w, snapshot.foods.len() as u32);
+                for food in &snapshot.foods {
+                    food.iter().for_each(|&value| write_f32(w, value));
+                }
+            }
+            Event::Generation {
+                generation,
+                min,
+                max,
+                avg,
+                cohesion,
+            } => {
+                w.push(1);
+                write_u32(w, *generation);
+                write_f32(w, *min);
+                write_f32(w, *max);
+                write_f32(w, *avg);
+                write_f32(w, *cohesion);
+            }
+            Event::Population(json) => {
+                w.push(2);
+                write_str(w, json);
+            }
+        }
+    }
+
+    fn read_from(r: &mut &[u8]) -> io::Result<Self> {
+        let event = match read_u8(r)? {
+            0 => {
+                let generation = read_u32(r)?;
+
+                let mut animals = Vec::new();
+                for _ in 0..read_u32(r)? {
+                    animals.push([read_f32(r)?, read_f32(r)?, read_f32(r)?, read_f32(r)?]);
+                }
+
+                let mut foods = Vec::new();
+                for _ in 0..read_u32(r)? {
+                    foods.push([read_f32(r)?, read_f32(r)?, read_f32(r)?, read_f32(r)?]);
+                }
+
+                Event::Snapshot(Snapshot {
+                    generation,
+                    animals,
+                    foods,
+                })
+            }
+            1 => Event::Generation {
+                generation: read_u32(r)?,
+                min: read_f32(r)?,
+                max: read_f32(r)?,
+                avg: read_f32(r)?,
+                cohesion: read_f32(r)?,
+            },
+            2 => Event::Population(read_str(r)?),
+            _ => return Err(invalid_data("unknown event")),
+        };
+
+        Ok(event)
+    }
+}
+
+/// Owns the simulation inside the worker and turns commands into events.
+pub struct Host<R> {
+    simulation: sim::Simulation,
+    rng: R,
+    running: bool,
+    speed: u32,
+}
+
+impl<R> Host<R>
+where
+    R: RngCore,
+{
+    pub fn new(simulation: sim::Simulation, rng: R) -> Self {
+        Self {
+            simulation,
+            rng,
+            running: false,
+            speed: 1,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn handle(&mut self, command: Command) -> io::Result<Vec<Event>> {
+        let events = match command {
+            Command::Start => {
+                self.running = true;
+                Vec::new()
+            }
+            Command::Pause => {
+                self.running = false;
+                Vec::new()
+            }
+            Command::SetSpeed(speed) => {
+                self.speed = speed.min(MAX_SPEED);
+                Vec::new()
+            }
+            Command::Snapshot => vec![Event::Snapshot(Snapshot::new(&self.simulation))],
+            Command::ExportPopulation => {
+                vec![Event::Population(
+                    self.simulation.checkpoint(None).to_json(),
+                )]
+            }
+            Command::ImportPopulation(json) => {
+                let checkpoint = sim::Checkpoint::from_json(&json)?;
+                (self.simulation, _) = sim::Simulation::from_checkpoint(checkpoint);
+                Vec::new()
+            }
+        };
+
+        Ok(events)
+    }
+
+    /// Run `speed` steps when started, reporting every generation that
+    /// ended along the way
+    pub fn tick(&mut self) -> Vec<Event> {
+        if !self.running {
+            return Vec::new();
+        }
+
+        (0..self.speed)
+            .filter_map(|_| self.simulation.step(&mut self.rng))
+            .map(|(stats, info)| Event::Generation {
+                generation: info.generation() as u32,
+                min: stats.min_fitness,
+                max: stats.max_fitness,
+                avg: stats.average_fitness,
+                cohesion: info.cohesion(),
+            })
+            .collect()
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_u32(w: &mut Vec<u8>, value: u32) {
+    w.write_all(&value.to_le_bytes())
+        .expect("writing to a Vec can't fail");
+}
+
+fn write_f32(w: &mut Vec<u8>, value: f32) {
+    w.write_all(&value.to_le_bytes())
+        .expect("writing to a Vec can't fail");
+}
+
+fn write_str(w: &mut Vec<u8>, value: &str) {
+    write_u32(w, value.len() as u32);
+    w.extend_from_slice(value.as_bytes());
+}
+
+fn read_version(r: &mut &[u8]) -> io::Result<()> {
+    if read_u8(r)? != VERSION {
+        return Err(invalid_data("unsupported protocol version"));
+    }
+
+    Ok(())
+}
+
+fn read_u8(r: &mut &[u8]) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u32(r: &mut &[u8]) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f32(r: &mut &[u8]) -> io::Result<f32> {
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(f32::from_le_bytes(bytes))
+}
+
+fn read_str(r: &mut &[u8]) -> io::Result<String> {
+    let len = read_u32(r)? as usize;
+    if len > r.len() {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    let (bytes, rest) = r.split_at(len);
+    *r = rest;
+
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid_data("string is not UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use sim::ChaCha8Rng;
+
+    use super::*;
+
+    fn host() -> Host<ChaCha8Rng> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let config = sim::Config {
+            generation_length: 50,
+            ..sim::Config::default()
+        };
+        let simulation = sim::Simulation::random_with_config(&mut rng, config, 5, 100).unwrap();
+
+        Host::new(simulation, rng)
+    }
+
+    #[test]
+    fn commands_roundtrip() {
+        let commands = [
+            Command::Start,
+            Command::Pause,
+            Command::SetSpeed(100),
+            Command::Snapshot,
+            Command::ExportPopulation,
+            Command::ImportPopulation("{}".into()),
+        ];
+
+        for command in commands {
+            assert_eq!(Command::decode(&command.encode()).unwrap(), command);
+        }
+
+        assert!(Command::decode(&[]).is_err());
+        assert!(Command::decode(&[VERSION]).is_err());
+        assert!(Command::decode(&[VERSION + 1, 0]).is_err());
+        assert!(Command::decode(&[VERSION, 9]).is_err());
+        assert!(Command::decode(&[VERSION, 2, 1]).is_err());
+        assert!(Command::decode(&[VERSION, 0, 0]).is_err());
+        assert!(Command::decode(&[VERSION, 5, 10, 0, 0, 0, b'{']).is_err());
+    }
+
+    #[test]
+    fn events_roundtrip() {
+        let events = vec![
+            Event::Snapshot(Snapshot {
+                generation: 3,
+                animals: vec![[0.1, 0.2, 0.3, 0.4], [0.5, 0.6, 0.7, 0.0]],
+                foods: vec![[0.8, 0.9, 1.0, 0.0]],
+            }),
+            Event::Generation {
+                generation: 4,
+                min: 0.0,
+                max: 5.0,
+                avg: 1.5,
+                cohesion: 0.25,
+            },
+            Event::Population("{}".into()),
+        ];
+
+        let bytes = Event::encode_batch(&events);
+        assert_eq!(Event::decode_batch(&bytes).unwrap(), events);
+        assert!(Event::decode_batch(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Event::decode_batch(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(Event::decode_batch(&[&[VERSION + 1], &bytes[1..]].concat()).is_err());
+    }
+
+    #[test]
+    fn snapshot_keeps_every_food() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let config = sim::Config {
+            food_regrowth: 1000,
+            ..sim::Config::default()
+        };
+        let mut simulation =
+            sim::Simulation::random_with_config(&mut rng, config, 10, 300).unwrap();
+
+        for _ in 0..200 {
+            simulation.step(&mut rng);
+        }
+
+        // Foods waiting to grow back stay where they are in the world
+        let snapshot = Snapshot::new(&simulation);
+        let foods = simulation.world().foods();
+        assert_eq!(snapshot.foods.len(), foods.len());
+        assert!(snapshot.foods.iter().any(|food| food[3] == 0.0));
+
+        for (food, sent) in foods.iter().zip(&snapshot.foods) {
+            assert_eq!(sent[..2], [food.position().x, food.position().y]);
+            assert_eq!(sent[3] == 1.0, food.is_available());
+        }
+    }
+
+    #[test]
+    fn host_runs_only_when_started() {
+        let mut host = host();
+        assert!(host.tick().is_empty());
+
+        host.handle(Command::SetSpeed(120)).unwrap();
+        host.handle(Command::Start).unwrap();
+
+        let events = host.tick();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], Event::Generation { generation: 2, .. }));
+
+        host.handle(Command::Pause).unwrap();
+        assert!(host.tick().is_empty());
+
+        host.handle(Command::SetSpeed(u32::MAX)).unwrap();
+        assert_eq!(host.speed, MAX_SPEED);
+
+        let snapshot = host.handle(Command::Snapshot).unwrap();
+        let [Event::Snapshot(snapshot)] = snapshot.as_slice() else {
+            panic!("expected a snapshot");
+        };
+        assert_eq!(snapshot.generation, 2);
+        assert_eq!((snapshot.animals.len(), snapshot.foods.len()), (5, 100));
+    }
+
+    #[test]
+    fn host_exports_and_imports() {
+        let mut host = host();
+        host.handle(Command::SetSpeed(60)).unwrap();
+        host.handle(Command::Start).unwrap();
+        host.tick();
+
+        let events = host.handle(Command::ExportPopulation).unwrap();
+        let [Event::Population(json)] = events.as_slice() else {
+            panic!("expected a population");
+        };
+
+        let mut other = self::host();
+        other
+            .handle(Command::ImportPopulation(json.clone()))
+            .unwrap();
+        assert_eq!(
+            other.handle(Command::Snapshot).unwrap(),
+            host.handle(Command::Snapshot).unwrap()
+        );
+
+        assert!(
+            other
+                .handle(Command::ImportPopulation("nope".into()))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use lib_simulation::{Config, Simulation};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const FOODS: usize = 60;
+
+fn vision(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+    let mut group = c.benchmark_group("vision");
+
+    for foods in [60, 200, 1000] {
+        let simulation = Simulation::random(&mut rng, 1, foods);
+        let world = simulation.world();
+        let animal = &world.animals()[0];
+
+        group.bench_function(BenchmarkId::from_parameter(foods), |b| {
+            b.iter(|| animal.vision(black_box(world)))
+        });
+    }
+
+    group.finish();
+}
+
+fn step(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+    let mut group = c.benchmark_group("step");
+
+    for animals in [20, 100, 500] {
+        let mut simulation = Simulation::random(&mut rng, animals, FOODS);
+
+        group.bench_function(BenchmarkId::from_parameter(animals), |b| {
+            b.iter(|| simulation.step(&mut rng))
+        });
+    }
+
+    group.finish();
+}
+
+fn train(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+    let mut group = c.benchmark_group("train");
+    group.sample_size(10);
+
+    // Shorter generations than the default, so a sample doesn't take
+    // seconds; the cost per step is the same
+    let config = Config {
+        generation_length: 250,
+        ..Config::default()
+    };
+
+    for animals in [20, 100] {
+        let mut simulation =
+            Simulation::random_with_config(&mut rng, config.clone(), animals, FOODS).unwrap();
+
+        group.bench_function(BenchmarkId::from_parameter(animals), |b| {
+            b.iter(|| simulation.train(&mut rng))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, vision, step, train);
+criterion_main!(benches);
@@ -1,8 +1,13 @@
 use lib_genetic_algorithm as ga;
+use lib_neural_network as nn;
 use nalgebra as na;
 use rand::{Rng, RngCore};
 
-use crate::{brain::Brain, eye::Eye};
+use crate::{
+    Config, FoodKind, World,
+    brain::Brain,
+    eye::{Eye, Sighting},
+};
 
 pub struct Animal {
     pub(crate) positon: na::Point2<f32>,
@@ -11,10 +16,27 @@ pub struct Animal {
     pub(crate) eye: Eye,
     pub(crate) brain: Brain,
     pub(crate) satiation: usize,
+    /// Poisonous foods eaten in the current generation
+    pub(crate) poisoned: usize,
+    pub(crate) energy: f32,
+    /// Steps this bird has been alive in the current generation
+    pub(crate) survived: usize,
+    /// Foods eaten since it was born or last reproduced
+    pub(crate) fed: usize,
+    /// How far it has flown in the current generation
+    pub(crate) distance: f32,
+    /// Steps since it last ate
+    pub(crate) hunger: usize,
+    /// Whether the brain last wanted to eat, see `BrainOutputs::eat`
+    pub(crate) eating: bool,
+    /// Whether the brain last wanted to boost, see `BrainOutputs::boost`
+    pub(crate) boosting: bool,
+    /// Signal it emits, from 0 to 1, see `BrainOutputs::signal`
+    pub(crate) signal: f32,
 }
 
 impl Animal {
-    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
+    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore, config: &Config) -> Self {
         Self {
             positon: rng.random(),
             rotation: rng.random(),
@@ -22,13 +44,22 @@ impl Animal {
             eye,
             brain,
             satiation: 0,
+            poisoned: 0,
+            energy: config.energy_max,
+            survived: 0,
+            fed: 0,
+            distance: 0.0,
+            hunger: 0,
+            eating: true,
+            boosting: false,
+            signal: 0.0,
         }
     }
 
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::random(rng, &eye);
-        Self::new(eye, brain, rng)
+    pub fn random(rng: &mut dyn RngCore, config: &Config) -> Self {
+        let eye = Eye::from_config(config);
+        let brain = Brain::random(rng, config);
+        Self::new(eye, brain, rng, config)
     }
 
     pub fn position(&self) -> na::Point2<f32> {
@@ -39,11 +70,166 @@ impl Animal {
         self.rotation
     }
 
-    pub(crate) fn from_chromosome(chromosome: ga::Crhomosome, rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::from_crhomosome(chromosome, &eye);
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// How far it flies in a step, boosting included
+    pub fn flying_speed(&self, config: &Config) -> f32 {
+        if self.boosting {
+            self.speed * config.boost_factor
+        } else {
+            self.speed
+        }
+    }
+
+    /// Signal it emits for other birds to see, from 0 to 1
+    pub fn signal(&self) -> f32 {
+        self.signal
+    }
+
+    /// How much food this bird ate in the current generation
+    pub fn satiation(&self) -> usize {
+        self.satiation
+    }
+
+    /// How many poisonous foods this bird ate in the current generation
+    pub fn poisoned(&self) -> usize {
+        self.poisoned
+    }
+
+    /// Energy left; birds stop moving, thinking and eating once it runs out
+    pub fn energy(&self) -> f32 {
+        self.energy
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.energy > 0.0
+    }
+
+    /// Steps this bird has been alive in the current generation
+    pub fn survived(&self) -> usize {
+        self.survived
+    }
+
+    /// How far this bird has flown in the current generation
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    /// Start counting what it does in a new steady-state period from
+    /// scratch, as if it were a new generation
+    pub(crate) fn start_period(&mut self) {
+        self.satiation = 0;
+        self.poisoned = 0;
+        self.survived = 0;
+        self.distance = 0.0;
+    }
+
+    /// Spend the energy of moving for one step, dying if it runs out
+    pub(crate) fn burn_energy(&mut self, config: &Config) {
+        self.energy -=
+            config.energy_idle_cost + config.energy_speed_cost * self.flying_speed(config);
+        self.die_if_drained();
+    }
+
+    pub(crate) fn eat(&mut self, config: &Config, kind: &FoodKind) {
+        if kind.poisonous {
+            self.poisoned += 1;
+        } else {
+            self.satiation += 1;
+            self.fed += 1;
+            self.hunger = 0;
+        }
+
+        self.energy =
+            (self.energy + kind.nutrition * config.energy_per_food).min(config.energy_max);
+        self.die_if_drained();
+    }
+
+    fn die_if_drained(&mut self) {
+        if self.energy <= 0.0 {
+            self.energy = 0.0;
+            self.speed = 0.0;
+        }
+    }
+
+    /// What the eye currently sees in `world`, one value per cell; see
+    /// `Simulation::brain_inputs` for everything the brain gets
+    pub fn vision(&self, world: &World) -> Vec<f32> {
+        let mut cells = self
+            .eye
+            .process_vision(self.positon, self.rotation, &world.foods);
+
+        let others = world
+            .animals
+            .iter()
+            .filter(|other| !std::ptr::eq(*other, self) && other.is_alive())
+            .map(Animal::sighting);
+        self.eye
+            .process_birds_into(self.positon, self.rotation, others, &mut cells);
+
+        cells
+    }
+
+    /// How other birds' eyes see this one
+    pub(crate) fn sighting(&self) -> Sighting {
+        Sighting {
+            position: self.positon,
+            rotation: self.rotation,
+            signal: self.signal,
+        }
+    }
+
+    /// Append the extra inputs of the brain enabled in `config`, `age`
+    /// steps into the generation
+    pub(crate) fn senses_into(&self, config: &Config, age: usize, inputs: &mut Vec<f32>) {
+        let senses = config.brain_inputs;
+        let generation_length = config.generation_length as f32;
+
+        if senses.speed {
+            let speed = if config.speed_max > 0.0 {
+                self.speed / config.speed_max
+            } else {
+                0.0
+            };
+            inputs.push(speed);
+        }
+        if senses.compass {
+            let (sin, cos) = self.rotation.angle().sin_cos();
+            inputs.extend([sin, cos]);
+        }
+        if senses.energy {
+            inputs.push(self.energy / config.energy_max);
+        }
+        if senses.hunger {
+            inputs.push((self.hunger as f32 / generation_length).min(1.0));
+        }
+        if senses.clock {
+            inputs.push((age as f32 / generation_length).min(1.0));
+        }
+    }
+
+    /// The neural network driving this bird
+    pub fn brain(&self) -> &nn::Network {
+        &self.brain.nn
+    }
+
+    /// Raw outputs of the brain for `vision`, before they're clamped into
+    /// a change of speed and rotation
+    pub fn brain_outputs(&self, vision: Vec<f32>) -> Vec<f32> {
+        self.brain.nn.propagate(vision)
+    }
+
+    pub(crate) fn from_chromosome(
+        chromosome: ga::Crhomosome,
+        rng: &mut dyn RngCore,
+        config: &Config,
+    ) -> Self {
+        let eye = Eye::from_config(config);
+        let brain = Brain::from_crhomosome(chromosome, config);
 
-        Self::new(eye, brain, rng)
+        Self::new(eye, brain, rng, config)
     }
 
     pub(crate) fn as_crhomosome(&self) -> ga::Crhomosome {
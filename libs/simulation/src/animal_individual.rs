@@ -1,6 +1,6 @@
 use lib_genetic_algorithm as ga;
 
-use crate::Animal;
+use crate::{Animal, Config};
 
 pub struct AnimalIndividual {
     pub fitness: f32,
@@ -8,8 +8,15 @@ pub struct AnimalIndividual {
 }
 
 impl AnimalIndividual {
-    pub fn into_animal(self, rng: &mut dyn rand::RngCore) -> Animal {
-        Animal::from_chromosome(self.chromosome, rng)
+    pub(crate) fn new(animal: &Animal, fitness: f32) -> Self {
+        Self {
+            fitness,
+            chromosome: animal.as_crhomosome(),
+        }
+    }
+
+    pub fn into_animal(self, rng: &mut dyn rand::RngCore, config: &Config) -> Animal {
+        Animal::from_chromosome(self.chromosome, rng, config)
     }
 }
 
@@ -29,12 +36,3 @@ impl ga::Individual for AnimalIndividual {
         self.fitness
     }
 }
-
-impl From<&Animal> for AnimalIndividual {
-    fn from(animal: &Animal) -> Self {
-        Self {
-            fitness: animal.satiation as f32,
-            chromosome: animal.as_crhomosome(),
-        }
-    }
-}
@@ -1,40 +1,52 @@
 use lib_genetic_algorithm as ga;
 use lib_neural_network as nn;
 use rand::RngCore;
+use serde::{Deserialize, Serialize};
 
-use crate::eye::Eye;
+use crate::{Config, eye::Eye};
 
 pub struct Brain {
     pub(crate) nn: nn::Network,
 }
 
 impl Brain {
-    pub fn random(rng: &mut dyn RngCore, eye: &Eye) -> Self {
+    pub fn random(rng: &mut dyn RngCore, config: &Config) -> Self {
         Self {
-            nn: nn::Network::random(rng, &Self::topology(eye)),
+            nn: nn::Network::random(rng, &Self::topology(config)),
         }
     }
 
-    fn topology(eye: &Eye) -> [nn::LayerTopology; 3] {
-        [
-            // every eye's cell is a input
-            nn::LayerTopology {
-                neurons: eye.cells(),
-            },
-            // hidden layer
-            nn::LayerTopology {
-                neurons: 2 * eye.cells(),
-            },
-            // this cotrols the speed and direction
-            nn::LayerTopology {
-                neurons: 2 * eye.cells(),
-            },
-        ]
-    }
-
-    pub(crate) fn from_crhomosome(crhomosome: ga::Crhomosome, eye: &Eye) -> Self {
+    fn topology(config: &Config) -> Vec<nn::LayerTopology> {
+        // every eye's cell is a input, once per kind of thing it sees,
+        // followed by the extra inputs
+        let input = nn::LayerTopology::new(Eye::inputs(config) + config.brain_inputs.count());
+
+        // hidden layers
+        let hidden = config.brain_hidden_layers.iter().map(|layer| {
+            nn::LayerTopology::new(layer.neurons).with_activation(layer.activation.into())
+        });
+
+        // this cotrols the speed and direction, and whatever else the
+        // bird can do
+        let output = nn::LayerTopology::new(config.brain_outputs.count());
+
+        std::iter::once(input)
+            .chain(hidden)
+            .chain(std::iter::once(output))
+            .collect()
+    }
+
+    /// Length of the chromosomes brains built from `config` have
+    pub(crate) fn gene_count(config: &Config) -> usize {
+        Self::topology(config)
+            .windows(2)
+            .map(|layers| (layers[0].neurons + 1) * layers[1].neurons)
+            .sum()
+    }
+
+    pub(crate) fn from_crhomosome(crhomosome: ga::Crhomosome, config: &Config) -> Self {
         Self {
-            nn: nn::Network::from_weights(&Self::topology(eye), crhomosome),
+            nn: nn::Network::from_weights(&Self::topology(config), crhomosome),
         }
     }
 
@@ -42,3 +54,119 @@ impl Brain {
         self.nn.weights().collect()
     }
 }
+
+/// Extra inputs of the brain besides the eye, each one off by default.
+///
+/// They're fed after the eye cells, in the order of the fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrainInputs {
+    /// Current speed, from 0 to 1 at `Config::speed_max`
+    pub speed: bool,
+    /// Sine and cosine of the heading, so it's two inputs
+    pub compass: bool,
+    /// Energy left, from 0 to 1 at `Config::energy_max`
+    pub energy: bool,
+    /// Steps since the last meal, from 0 to 1 after a whole generation
+    pub hunger: bool,
+    /// How far into the generation we are, from 0 to 1
+    pub clock: bool,
+}
+
+impl BrainInputs {
+    pub const ALL: Self = Self {
+        speed: true,
+        compass: true,
+        energy: true,
+        hunger: true,
+        clock: true,
+    };
+
+    /// How many inputs these add to the brain
+    pub fn count(&self) -> usize {
+        usize::from(self.speed)
+            + 2 * usize::from(self.compass)
+            + usize::from(self.energy)
+            + usize::from(self.hunger)
+            + usize::from(self.clock)
+    }
+}
+
+/// What the outputs of the brain drive. Speed and rotation always come
+/// first, then one output per action enabled here, in the order of the
+/// fields; an action happens when its output is positive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrainOutputs {
+    /// Only eat the foods reached while wanting to; birds without this
+    /// output eat everything they reach
+    pub eat: bool,
+    /// Fly `Config::boost_factor` times faster, burning energy to match
+    pub boost: bool,
+    /// Emit a signal other birds can see, as strong as the output up to 1;
+    /// needs `Config::eye_birds`
+    pub signal: bool,
+}
+
+impl BrainOutputs {
+    pub const ALL: Self = Self {
+        eat: true,
+        boost: true,
+        signal: true,
+    };
+
+    /// How many outputs the brain has, speed and rotation included
+    pub fn count(&self) -> usize {
+        2 + usize::from(self.eat) + usize::from(self.boost) + usize::from(self.signal)
+    }
+
+    /// What every output of the brain drives, in order
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names = vec!["speed", "rotation"];
+        if self.eat {
+            names.push("eat");
+        }
+        if self.boost {
+            names.push("boost");
+        }
+        if self.signal {
+            names.push("signal");
+        }
+        names
+    }
+}
+
+/// A hidden layer of the brain
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HiddenLayer {
+    pub neurons: usize,
+    pub activation: Activation,
+}
+
+impl HiddenLayer {
+    /// A layer of ReLU neurons, like the brain always had
+    pub fn new(neurons: usize) -> Self {
+        Self {
+            neurons,
+            activation: Activation::Relu,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Activation {
+    #[default]
+    Relu,
+    Sigmoid,
+    Tanh,
+    Identity,
+}
+
+impl From<Activation> for nn::Activation {
+    fn from(activation: Activation) -> Self {
+        match activation {
+            Activation::Relu => nn::Activation::Relu,
+            Activation::Sigmoid => nn::Activation::Sigmoid,
+            Activation::Tanh => nn::Activation::Tanh,
+            Activation::Identity => nn::Activation::Identity,
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    fs,
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use lib_genetic_algorithm as ga;
+use nalgebra as na;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Animal, Config, Food, FoodPatch, HallOfFame, World, brain::Brain, eye::Eye};
+
+/// Everything needed to continue a simulation exactly where it was left.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub(crate) config: Config,
+    pub(crate) generation: usize,
+    pub(crate) age: usize,
+    pub(crate) history: Vec<ga::Statistics>,
+    pub(crate) hall_of_fame: HallOfFame,
+    pub(crate) animals: Vec<AnimalState>,
+    pub(crate) foods: Vec<Food>,
+    pub(crate) food_patches: Vec<FoodPatch>,
+    pub(crate) food_respawns: usize,
+    /// Only present when the simulation was driven by a seeded RNG
+    pub(crate) rng: Option<ChaCha8Rng>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct AnimalState {
+    position: na::Point2<f32>,
+    rotation: na::Rotation2<f32>,
+    speed: f32,
+    satiation: usize,
+    poisoned: usize,
+    energy: f32,
+    survived: usize,
+    fed: usize,
+    distance: f32,
+    hunger: usize,
+    eating: bool,
+    boosting: bool,
+    signal: f32,
+    chromosome: ga::Crhomosome,
+}
+
+impl Checkpoint {
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+
+        // Write next to the target and rename, so a crash while writing
+        // never leaves a truncated checkpoint behind
+        let tmp = path.with_extension("tmp");
+        let mut writer = BufWriter::new(fs::File::create(&tmp)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(tmp, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(fs::File::open(path)?);
+        let checkpoint: Self = serde_json::from_reader(reader)?;
+
+        checkpoint.validate()?;
+        Ok(checkpoint)
+    }
+
+    /// Same format `save` writes
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("checkpoint is serializable")
+    }
+
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let checkpoint: Self = serde_json::from_str(json)?;
+
+        checkpoint.validate()?;
+        Ok(checkpoint)
+    }
+
+    /// Reject checkpoints that would panic once restored, e.g. with
+    /// chromosomes that don't fit the configured brain or foods of kinds
+    /// that aren't configured
+    fn validate(&self) -> io::Result<()> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        self.config
+            .validate()
+            .map_err(|err| invalid(err.to_string()))?;
+
+        let genes = Brain::gene_count(&self.config);
+        if let Some(state) = self.animals.iter().find(|s| s.chromosome.len() != genes) {
+            return Err(invalid(format!(
+                "chromosome has {} genes, the brain needs {genes}",
+                state.chromosome.len()
+            )));
+        }
+
+        let kinds = self.config.food_kinds.len();
+        if let Some(food) = self.foods.iter().find(|food| food.kind >= kinds) {
+            return Err(invalid(format!(
+                "food is of kind {}, only {kinds} are configured",
+                food.kind
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn world(&self) -> World {
+        let animals = self
+            .animals
+            .iter()
+            .map(|state| {
+                let eye = Eye::from_config(&self.config);
+                let brain = Brain::from_crhomosome(state.chromosome.clone(), &self.config);
+
+                Animal {
+                    positon: state.position,
+                    rotation: state.rotation,
+                    speed: state.speed,
+                    eye,
+                    brain,
+                    satiation: state.satiation,
+                    poisoned: state.poisoned,
+                    energy: state.energy,
+                    survived: state.survived,
+                    fed: state.fed,
+                    distance: state.distance,
+                    hunger: state.hunger,
+                    eating: state.eating,
+                    boosting: state.boosting,
+                    signal: state.signal,
+                }
+            })
+            .collect();
+
+        World {
+            animals,
+            foods: self.foods.clone(),
+            patches: self.food_patches.clone(),
+            respawns: self.food_respawns,
+        }
+    }
+}
+
+impl From<&Animal> for AnimalState {
+    fn from(animal: &Animal) -> Self {
+        Self {
+            position: animal.positon,
+            rotation: animal.rotation,
+            speed: animal.speed,
+            satiation: animal.satiation,
+            poisoned: animal.poisoned,
+            energy: animal.energy,
+            survived: animal.survived,
+            fed: animal.fed,
+            distance: animal.distance,
+            hunger: animal.hunger,
+            eating: animal.eating,
+            boosting: animal.boosting,
+            signal: animal.signal,
+            chromosome: animal.as_crhomosome(),
+        }
+    }
+}
+
+/// Where and how often `Simulation` writes checkpoints
+pub(crate) struct Checkpointing {
+    pub(crate) every: usize,
+    pub(crate) path: PathBuf,
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{HiddenLayer, Simulation};
+
+    #[test]
+    fn json_roundtrip() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let simulation = Simulation::random(&mut rng, 4, 10);
+        let json = simulation.checkpoint(None).to_json();
+
+        let checkpoint = Checkpoint::from_json(&json).unwrap();
+        assert_eq!(checkpoint.to_json(), json);
+        assert_eq!(
+            checkpoint.animals[0].chromosome.len(),
+            Brain::gene_count(&checkpoint.config)
+        );
+    }
+
+    #[test]
+    fn rejects_chromosomes_that_dont_fit() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let simulation = Simulation::random(&mut rng, 4, 10);
+
+        let mut checkpoint = simulation.checkpoint(None);
+        checkpoint.config.brain_hidden_layers = vec![HiddenLayer::new(3)];
+
+        assert!(matches!(
+            Checkpoint::from_json(&checkpoint.to_json()),
+            Err(err) if err.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_food_kinds() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let simulation = Simulation::random(&mut rng, 4, 10);
+
+        let mut checkpoint = simulation.checkpoint(None);
+        checkpoint.foods[3].kind = checkpoint.config.food_kinds.len();
+
+        assert!(matches!(
+            Checkpoint::from_json(&checkpoint.to_json()),
+            Err(err) if err.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+}
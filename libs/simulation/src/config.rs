@@ -0,0 +1,381 @@
+use std::{f32::consts::FRAC_PI_2, fmt};
+
+use lib_genetic_algorithm::{self as ga, Individual};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{BrainInputs, BrainOutputs, FoodKind, HiddenLayer, eye};
+
+/// Tunables of a simulation, defaults match the original constants; the
+/// energy model the original didn't have is off until its costs are set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// Minimum speed of a bird
+    pub speed_min: f32,
+    /// Maximum speed of a bird
+    pub speed_max: f32,
+    /// How much the speed can change in a single step
+    pub speed_accel: f32,
+    /// How much the rotation can change in a single step
+    pub rotation_accel: f32,
+    /// How many times faster a boosting bird flies, see
+    /// `BrainOutputs::boost`
+    pub boost_factor: f32,
+
+    /// How many steps each generation lasts; in steady-state mode it's
+    /// only how often statistics are collected
+    pub generation_length: usize,
+
+    /// Whether the population is replaced all at once or bird by bird
+    pub evolution: Evolution,
+
+    /// Energy a bird starts with, and the most it can store
+    pub energy_max: f32,
+    /// Energy restored by eating a food
+    pub energy_per_food: f32,
+    /// Energy spent every step just by being alive; with no costs birds
+    /// never starve
+    pub energy_idle_cost: f32,
+    /// Energy spent every step per unit of speed
+    pub energy_speed_cost: f32,
+    /// Fitness a bird gets for surviving the whole generation, on top of
+    /// one per food eaten; surviving part of it gives a proportional part.
+    /// Only used by the default fitness function, which is just the food
+    /// eaten when this is 0
+    pub fitness_survival: f32,
+
+    /// Kinds of food, seen by the eye as separate channels
+    pub food_kinds: Vec<FoodKind>,
+    /// How many patches foods grow around; 0 spreads them uniformly
+    pub food_patches: usize,
+    /// How far from the center of its patch a food can grow
+    pub food_patch_radius: f32,
+    /// How far patches drift in a single step
+    pub food_patch_drift: f32,
+    /// Steps an eaten food takes to grow back somewhere else; 0 grows it
+    /// back right away
+    pub food_regrowth: usize,
+    /// How many eaten foods grow back every generation; once spent,
+    /// eaten foods are gone until the next one
+    pub food_budget: Option<usize>,
+    /// Steps a full cycle of seasons lasts; 0 disables seasons
+    pub food_season_length: usize,
+    /// Share of the foods gone at the worst point of the season
+    pub food_season_amplitude: f32,
+
+    /// See `eye::FOV_RANGE`
+    pub eye_fov_range: f32,
+    /// See `eye::FOV_ANGLE`
+    pub eye_fov_angle: f32,
+    /// See `eye::CELLS`
+    pub eye_cells: usize,
+    /// Whether eyes also see other birds, where they are and where they
+    /// fly, on two extra sets of cells
+    pub eye_birds: bool,
+
+    /// Probability of mutating a gene
+    pub mutation_chance: f32,
+    /// Magnitude of a gene mutation
+    pub mutation_coeff: f32,
+
+    /// How parents are picked for the next generation
+    pub selection: Selection,
+
+    /// What the brain gets to know besides what the eye sees
+    pub brain_inputs: BrainInputs,
+    /// What the brain can do besides changing speed and rotation
+    pub brain_outputs: BrainOutputs,
+    /// Every hidden layer of the brain, from the eye to the output layer;
+    /// with none, the eye drives the outputs directly
+    pub brain_hidden_layers: Vec<HiddenLayer>,
+
+    /// How many of the best birds ever seen are kept
+    pub hall_of_fame_size: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            speed_min: 0.001,
+            speed_max: 0.005,
+            speed_accel: 0.2,
+            rotation_accel: FRAC_PI_2,
+            boost_factor: 2.0,
+            generation_length: 2500,
+            evolution: Evolution::Generational,
+            energy_max: 1.0,
+            energy_per_food: 0.25,
+            energy_idle_cost: 0.0,
+            energy_speed_cost: 0.0,
+            fitness_survival: 0.0,
+            food_kinds: vec![FoodKind::NORMAL],
+            food_patches: 0,
+            food_patch_radius: 0.1,
+            food_patch_drift: 0.0005,
+            food_regrowth: 0,
+            food_budget: None,
+            food_season_length: 0,
+            food_season_amplitude: 0.5,
+            eye_fov_range: eye::FOV_RANGE,
+            eye_fov_angle: eye::FOV_ANGLE,
+            eye_cells: eye::CELLS,
+            eye_birds: false,
+            mutation_chance: 0.01,
+            mutation_coeff: 0.3,
+            selection: Selection::RouletteWheel,
+            brain_inputs: BrainInputs::default(),
+            brain_outputs: BrainOutputs::default(),
+            brain_hidden_layers: vec![HiddenLayer::new(2 * eye::CELLS)],
+            hall_of_fame_size: 10,
+        }
+    }
+}
+
+impl Config {
+    /// Check the values make sense, so building a simulation from them
+    /// won't panic
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let check = |ok: bool, field: &'static str, reason: &'static str| {
+            if ok {
+                Ok(())
+            } else {
+                Err(ConfigError { field, reason })
+            }
+        };
+
+        check(
+            self.speed_min.is_finite() && self.speed_min >= 0.0,
+            "speed_min",
+            "must be a non-negative number",
+        )?;
+        check(
+            self.speed_max.is_finite() && self.speed_max >= self.speed_min,
+            "speed_max",
+            "must be at least speed_min",
+        )?;
+        check(
+            self.speed_accel.is_finite() && self.speed_accel >= 0.0,
+            "speed_accel",
+            "must be a non-negative number",
+        )?;
+        check(
+            self.rotation_accel.is_finite() && self.rotation_accel >= 0.0,
+            "rotation_accel",
+            "must be a non-negative number",
+        )?;
+        check(
+            self.boost_factor.is_finite() && self.boost_factor >= 1.0,
+            "boost_factor",
+            "must be at least 1",
+        )?;
+        check(
+            self.generation_length > 0,
+            "generation_length",
+            "must be at least 1",
+        )?;
+        check(
+            !matches!(
+                self.evolution,
+                Evolution::SteadyState {
+                    foods_to_reproduce: 0
+                }
+            ),
+            "evolution",
+            "foods to reproduce must be at least 1",
+        )?;
+        check(
+            self.energy_max.is_finite() && self.energy_max > 0.0,
+            "energy_max",
+            "must be a positive number",
+        )?;
+        check(
+            self.energy_per_food.is_finite() && self.energy_per_food >= 0.0,
+            "energy_per_food",
+            "must be a non-negative number",
+        )?;
+        check(
+            self.energy_idle_cost.is_finite() && self.energy_idle_cost >= 0.0,
+            "energy_idle_cost",
+            "must be a non-negative number",
+        )?;
+        check(
+            self.energy_speed_cost.is_finite() && self.energy_speed_cost >= 0.0,
+            "energy_speed_cost",
+            "must be a non-negative number",
+        )?;
+        check(
+            self.fitness_survival.is_finite() && self.fitness_survival >= 0.0,
+            "fitness_survival",
+            "must be a non-negative number",
+        )?;
+        check(
+            !self.food_kinds.is_empty()
+                && self
+                    .food_kinds
+                    .iter()
+                    .all(|kind| kind.share.is_finite() && kind.share >= 0.0)
+                && self.food_kinds.iter().any(|kind| kind.share > 0.0),
+            "food_kinds",
+            "needs at least one kind, with non-negative shares adding up to more than 0",
+        )?;
+        check(
+            self.food_kinds
+                .iter()
+                .all(|kind| kind.nutrition.is_finite()),
+            "food_kinds",
+            "nutrition must be a number",
+        )?;
+        check(
+            self.food_patch_radius.is_finite() && self.food_patch_radius > 0.0,
+            "food_patch_radius",
+            "must be a positive number",
+        )?;
+        check(
+            self.food_patch_drift.is_finite() && self.food_patch_drift >= 0.0,
+            "food_patch_drift",
+            "must be a non-negative number",
+        )?;
+        check(
+            (0.0..=1.0).contains(&self.food_season_amplitude),
+            "food_season_amplitude",
+            "must be between 0 and 1",
+        )?;
+        check(
+            self.eye_fov_range.is_finite() && self.eye_fov_range > 0.0,
+            "eye_fov_range",
+            "must be a positive number",
+        )?;
+        check(
+            self.eye_fov_angle.is_finite() && self.eye_fov_angle > 0.0,
+            "eye_fov_angle",
+            "must be a positive number",
+        )?;
+        check(self.eye_cells > 0, "eye_cells", "must be at least 1")?;
+        check(
+            (0.0..=1.0).contains(&self.mutation_chance),
+            "mutation_chance",
+            "must be between 0 and 1",
+        )?;
+        check(
+            self.mutation_coeff.is_finite() && self.mutation_coeff >= 0.0,
+            "mutation_coeff",
+            "must be a non-negative number",
+        )?;
+        check(
+            !matches!(self.selection, Selection::Tournament { size: 0 }),
+            "selection",
+            "tournament size must be at least 1",
+        )?;
+        check(
+            !self.brain_outputs.signal || self.eye_birds,
+            "brain_outputs",
+            "signals need eye_birds, or nobody would see them",
+        )?;
+        check(
+            self.brain_hidden_layers
+                .iter()
+                .all(|layer| layer.neurons > 0),
+            "brain_hidden_layers",
+            "every layer needs at least 1 neuron",
+        )?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Evolution {
+    /// Every `generation_length` steps, the whole population is replaced
+    /// by the children of the fittest birds and the world is reset
+    Generational,
+
+    /// The world never resets: a bird that eats `foods_to_reproduce`
+    /// foods has a child in place, which replaces the weakest bird
+    SteadyState { foods_to_reproduce: usize },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Selection {
+    RouletteWheel,
+    Tournament { size: usize },
+}
+
+impl ga::SelectionMethod for Selection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        match *self {
+            Selection::RouletteWheel => ga::RouletteWheelSelection.select(rng, population),
+            Selection::Tournament { size } => {
+                ga::TournamentSelection::new(size).select(rng, population)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    pub field: &'static str,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {}: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_valid() {
+        assert_eq!(Config::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let config = Config {
+            speed_max: 0.0,
+            ..Config::default()
+        };
+        assert_eq!(config.validate().unwrap_err().field, "speed_max");
+
+        let config = Config {
+            mutation_chance: 1.5,
+            ..Config::default()
+        };
+        assert_eq!(config.validate().unwrap_err().field, "mutation_chance");
+
+        let config = Config {
+            food_season_amplitude: -0.5,
+            ..Config::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err().field,
+            "food_season_amplitude"
+        );
+
+        let config = Config {
+            brain_outputs: BrainOutputs {
+                signal: true,
+                ..BrainOutputs::default()
+            },
+            ..Config::default()
+        };
+        assert_eq!(config.validate().unwrap_err().field, "brain_outputs");
+
+        let config = Config {
+            brain_hidden_layers: vec![HiddenLayer::new(4), HiddenLayer::new(0)],
+            ..Config::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "invalid brain_hidden_layers: every layer needs at least 1 neuron"
+        );
+    }
+}
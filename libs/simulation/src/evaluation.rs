@@ -0,0 +1,148 @@
+use lib_genetic_algorithm as ga;
+use nalgebra as na;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Animal, Config, Simulation, World};
+
+/// Outcome of running a single brain in a fresh world, without evolving.
+///
+/// Unlike the fitness measured during training, this only depends on the
+/// chromosome and the world's seed, so it can be used to compare brains
+/// on a fixed suite of worlds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Evaluation {
+    pub foods_eaten: usize,
+    /// State of the bird after every step
+    pub trajectory: Vec<TrajectoryPoint>,
+    /// How many foods could be eaten after every step, which the seasons
+    /// and regrowth change
+    pub foods_available: Vec<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TrajectoryPoint {
+    pub position: na::Point2<f32>,
+    pub rotation: f32,
+    pub speed: f32,
+}
+
+impl Evaluation {
+    /// Put a bird with `chromosome` in a world of `food_count` foods
+    /// generated from `seed`, and let it fly for `steps` steps
+    pub fn run(
+        config: &Config,
+        chromosome: &ga::Crhomosome,
+        seed: u64,
+        food_count: usize,
+        steps: usize,
+    ) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        let animal = Animal::from_chromosome(chromosome.clone(), &mut rng, config);
+        let world = World::with_animals(&mut rng, config, vec![animal], food_count);
+
+        let mut simulation = Simulation::new(config.clone(), world);
+        let mut trajectory = Vec::with_capacity(steps);
+        let mut foods_available = Vec::with_capacity(steps);
+
+        for _ in 0..steps {
+            simulation.process_foods();
+            simulation.process_collisions(&mut rng);
+            simulation.process_brains();
+            simulation.process_movement();
+
+            let animal = &simulation.world.animals[0];
+            trajectory.push(TrajectoryPoint {
+                position: animal.positon,
+                rotation: animal.rotation.angle(),
+                speed: animal.speed,
+            });
+
+            let foods = &simulation.world.foods;
+            foods_available.push(foods.iter().filter(|food| food.is_available()).count());
+
+            // Time goes on like in `Simulation::step`, so the clock and the
+            // seasons do too, but generations end without evolving
+            simulation.age += 1;
+            if simulation.age > config.generation_length {
+                simulation.generation += 1;
+                simulation.age = 0;
+            }
+        }
+
+        Self {
+            foods_eaten: simulation.world.animals[0].satiation,
+            trajectory,
+            foods_available,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BrainInputs, brain::Brain, eye::Eye};
+
+    #[test]
+    fn is_reproducible() {
+        let config = Config::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let chromosome = Animal::random(&mut rng, &config).as_crhomosome();
+
+        let a = Evaluation::run(&config, &chromosome, 42, 100, 500);
+        let b = Evaluation::run(&config, &chromosome, 42, 100, 500);
+        let c = Evaluation::run(&config, &chromosome, 43, 100, 500);
+
+        assert_eq!(a.trajectory.len(), 500);
+        assert_eq!(a.foods_eaten, b.foods_eaten);
+        assert_eq!(a.trajectory[499].position, b.trajectory[499].position);
+        assert_ne!(a.trajectory[0].position, c.trajectory[0].position);
+    }
+
+    #[test]
+    fn clock_moves_on() {
+        let config = Config {
+            brain_inputs: BrainInputs {
+                clock: true,
+                ..BrainInputs::default()
+            },
+            brain_hidden_layers: vec![],
+            ..Config::default()
+        };
+
+        // Only the clock speeds the bird up, nothing else moves its brain
+        let genes = Brain::gene_count(&config);
+        let clock = 1 + Eye::inputs(&config);
+        let chromosome = (0..genes)
+            .map(|i| if i == clock { 1.0 } else { 0.0 })
+            .collect();
+
+        let evaluation = Evaluation::run(&config, &chromosome, 42, 0, 100);
+        let speeds: Vec<_> = evaluation.trajectory.iter().map(|p| p.speed).collect();
+
+        assert!(speeds.windows(2).all(|pair| pair[1] >= pair[0]));
+        assert!(speeds[99] > speeds[0]);
+    }
+
+    #[test]
+    fn seasons_move_on() {
+        let config = Config {
+            food_season_length: 100,
+            food_season_amplitude: 1.0,
+            ..Config::default()
+        };
+
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let chromosome = Animal::random(&mut rng, &config).as_crhomosome();
+
+        let evaluation = Evaluation::run(&config, &chromosome, 42, 100, 100);
+        let available = &evaluation.foods_available;
+
+        // Halfway through the cycle is the depth of winter
+        assert!(available[0] > 0);
+        assert_eq!(available[50], 0);
+        assert!(available[99] > 0);
+    }
+}
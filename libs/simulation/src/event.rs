@@ -0,0 +1,29 @@
+use lib_genetic_algorithm as ga;
+use nalgebra as na;
+
+/// Something that happened during a step, see `Simulation::record_events`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    FoodEaten {
+        animal: usize,
+        food: usize,
+        /// Where the food was when it got eaten
+        position: na::Point2<f32>,
+    },
+    /// Always right after the food was eaten
+    FoodRespawned {
+        food: usize,
+        position: na::Point2<f32>,
+    },
+    /// A child took the place of the bird at `animal`, in steady-state
+    /// mode
+    AnimalBorn { animal: usize, parent: usize },
+    /// Ran out of energy, or got replaced by a newborn while still alive
+    AnimalDied { animal: usize },
+    /// Statistics were collected, and in generational mode the population
+    /// and foods were replaced
+    GenerationEnded {
+        generation: usize,
+        stats: ga::Statistics,
+    },
+}
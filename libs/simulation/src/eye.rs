@@ -1,7 +1,7 @@
 use nalgebra as na;
 use std::f32::consts::{FRAC_PI_4, PI};
 
-use crate::Food;
+use crate::{Config, Food};
 
 /// How far our eye can see:
 ///
@@ -20,7 +20,7 @@ use crate::Food;
 /// - 0.1 = 10% of the map = bird sees no foods (at least in this case)
 /// - 0.5 = 50% of the map = bird sees one of the foods
 /// - 1.0 = 100% of the map = bird sees both foods
-const FOV_RANGE: f32 = 0.25;
+pub(crate) const FOV_RANGE: f32 = 0.25;
 
 /// How wide our eye can see.
 ///
@@ -83,7 +83,7 @@ const FOV_RANGE: f32 = 0.25;
 ///   |      ---      |
 ///   |               |
 ///   -----------------
-const FOV_ANGLE: f32 = PI + FRAC_PI_4;
+pub(crate) const FOV_ANGLE: f32 = PI + FRAC_PI_4;
 
 /// How much photoreceptors there are in a single eye.
 ///
@@ -94,17 +94,23 @@ const FOV_ANGLE: f32 = PI + FRAC_PI_4;
 ///
 /// I've found values between 3~11 sufficient, with eyes having more
 /// than ~20 photoreceptors yielding progressively worse results.
-const CELLS: usize = 9;
+pub(crate) const CELLS: usize = 9;
 
 #[derive(Debug)]
 pub struct Eye {
     fov_range: f32,
     fov_angle: f32,
     cells: usize,
+    /// One set of cells per kind of food, see `Config::food_kinds`
+    channels: usize,
+    /// Whether it also sees other birds, see `Config::eye_birds`
+    birds: bool,
+    /// Whether it sees the signals birds emit, see `BrainOutputs::signal`
+    signals: bool,
 }
 
 impl Eye {
-    fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
+    pub(crate) fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
         assert!(fov_range > 0.0);
         assert!(fov_angle > 0.0);
         assert!(cells > 0);
@@ -112,55 +118,146 @@ impl Eye {
             fov_range,
             fov_angle,
             cells,
+            channels: 1,
+            birds: false,
+            signals: false,
         }
     }
 
-    pub fn cells(&self) -> usize {
-        self.cells
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            channels: config.food_kinds.len(),
+            birds: config.eye_birds,
+            signals: config.eye_birds && config.brain_outputs.signal,
+            ..Self::new(config.eye_fov_range, config.eye_fov_angle, config.eye_cells)
+        }
     }
 
+    /// How many values eyes built from `config` output in total
+    pub(crate) fn inputs(config: &Config) -> usize {
+        let bird_channels = match (config.eye_birds, config.brain_outputs.signal) {
+            (true, true) => 3,
+            (true, false) => 2,
+            (false, _) => 0,
+        };
+        config.eye_cells * (config.food_kinds.len() + bird_channels)
+    }
+
+    /// What the eye sees, one value per cell: `cells` values for the
+    /// first kind of food, then as many for the next kind and so on
     pub fn process_vision(
         &self,
         position: na::Point2<f32>,
         rotation: na::Rotation2<f32>,
         foods: &[Food],
     ) -> Vec<f32> {
-        let mut cells = vec![0.0; self.cells];
-        for food in foods {
-            let vec = food.position - position;
-            let dist = vec.norm();
+        let mut cells = Vec::new();
+        self.process_vision_into(position, rotation, foods, &mut cells);
+        cells
+    }
+
+    /// Like `process_vision`, but reuses `cells` instead of allocating
+    pub(crate) fn process_vision_into(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        foods: &[Food],
+        cells: &mut Vec<f32>,
+    ) {
+        cells.clear();
+        cells.resize(self.cells * self.channels, 0.0);
 
-            if dist >= self.fov_range {
+        for food in foods.iter().filter(|food| food.is_available()) {
+            let Some((cell, energy)) = self.locate(position, rotation, food.position) else {
                 continue;
-            }
+            };
 
-            let mut angle = na::Rotation2::rotation_between(&na::Vector2::y(), &vec).angle();
+            let cell = food.kind.min(self.channels - 1) * self.cells + cell;
+            cells[cell] += energy;
+        }
+    }
 
-            angle -= rotation.angle();
+    /// Append what the eye sees of other `birds`: `cells` values of how
+    /// close they are, then `cells` of how much they fly the same way
+    /// (negative when they fly the opposite way) and, if it sees signals,
+    /// `cells` of how strongly they signal. Appends nothing unless the eye
+    /// sees birds.
+    pub(crate) fn process_birds_into(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        birds: impl IntoIterator<Item = Sighting>,
+        cells: &mut Vec<f32>,
+    ) {
+        if !self.birds {
+            return;
+        }
 
-            angle = na::wrap(angle, -PI, PI);
+        let channels = if self.signals { 3 } else { 2 };
+        let start = cells.len();
+        cells.resize(start + channels * self.cells, 0.0);
 
-            if angle < -self.fov_angle / 2.0 || angle > self.fov_angle / 2.0 {
+        for bird in birds {
+            let Some((cell, energy)) = self.locate(position, rotation, bird.position) else {
                 continue;
+            };
+
+            let heading = bird.rotation.angle() - rotation.angle();
+            cells[start + cell] += energy;
+            cells[start + self.cells + cell] += energy * heading.cos();
+
+            if self.signals {
+                cells[start + 2 * self.cells + cell] += energy * bird.signal;
             }
+        }
+    }
+
+    /// Which cell sees `target`, and how strongly, if it's in sight at all
+    fn locate(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        target: na::Point2<f32>,
+    ) -> Option<(usize, f32)> {
+        let vec = target - position;
+        let dist = vec.norm();
+
+        if dist >= self.fov_range {
+            return None;
+        }
 
-            // < -fov_angle/2, +fov_angle/2> -> <0, fov_angle>
-            angle += self.fov_angle / 2.0;
+        let mut angle = na::Rotation2::rotation_between(&na::Vector2::y(), &vec).angle();
 
-            let cell = angle / self.fov_angle;
-            let cell = cell * (self.cells as f32);
+        angle -= rotation.angle();
 
-            // the unit test fails if the cell is clamped to the
-            let cell = (cell as usize).min(cells.len() - 1);
+        angle = na::wrap(angle, -PI, PI);
 
-            let energy = (self.fov_range - dist) / self.fov_range;
-            cells[cell as usize] += energy;
+        if angle < -self.fov_angle / 2.0 || angle > self.fov_angle / 2.0 {
+            return None;
         }
 
-        cells
+        // < -fov_angle/2, +fov_angle/2> -> <0, fov_angle>
+        angle += self.fov_angle / 2.0;
+
+        let cell = angle / self.fov_angle;
+        let cell = cell * (self.cells as f32);
+
+        // the unit test fails if the cell is clamped to the
+        let cell = (cell as usize).min(self.cells - 1);
+
+        let energy = (self.fov_range - dist) / self.fov_range;
+        Some((cell, energy))
     }
 }
 
+/// Another bird, as the eye sees it
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Sighting {
+    pub(crate) position: na::Point2<f32>,
+    pub(crate) rotation: na::Rotation2<f32>,
+    pub(crate) signal: f32,
+}
+
 impl Default for Eye {
     fn default() -> Self {
         Self::new(FOV_RANGE, FOV_ANGLE, CELLS)
@@ -221,10 +318,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn channels() {
+        let mut eye = Eye::new(1.0, FRAC_PI_2, 3);
+        eye.channels = 2;
+
+        let foods = [
+            food(0.5, 1.0),
+            Food {
+                kind: 1,
+                ..food(0.5, 1.0)
+            },
+        ];
+        let vision = eye.process_vision(na::Point2::new(0.5, 0.5), na::Rotation2::new(0.0), &foods);
+
+        assert_eq!(vision.len(), 6);
+        assert_eq!(vision[1], 0.5);
+        assert_eq!(vision[4], 0.5);
+        assert_eq!(vision.iter().sum::<f32>(), 1.0);
+    }
+
+    #[test]
+    fn birds() {
+        let mut eye = Eye::new(1.0, FRAC_PI_2, 3);
+        let position = na::Point2::new(0.5, 0.5);
+        let rotation = na::Rotation2::new(0.0);
+        let bird = |y: f32, rotation: f32, signal: f32| Sighting {
+            position: na::Point2::new(0.5, y),
+            rotation: na::Rotation2::new(rotation),
+            signal,
+        };
+        let birds = [bird(1.0, 0.0, 1.0), bird(0.75, PI, 0.0)];
+
+        let mut vision = vec![0.0];
+        eye.process_birds_into(position, rotation, birds, &mut vision);
+        assert_eq!(vision, [0.0]);
+
+        eye.birds = true;
+        eye.process_birds_into(position, rotation, birds, &mut vision);
+
+        // One bird flying along with us and a closer one flying at us
+        assert_eq!(vision.len(), 7);
+        assert_eq!(vision[2], 0.5 + 0.75);
+        assert!((vision[5] - (0.5 - 0.75)).abs() < 1e-6);
+        assert_eq!(vision[1] + vision[3] + vision[4] + vision[6], 0.0);
+
+        // Only the first bird signals
+        eye.signals = true;
+        eye.process_birds_into(position, rotation, birds, &mut vision);
+        assert_eq!(vision.len(), 7 + 9);
+        assert_eq!(vision[7 + 6..], [0.0, 0.5, 0.0]);
+    }
+
     fn food(x: f32, y: f32) -> Food {
-        Food {
-            position: na::Point2::new(x, y),
-        }
+        Food::at(na::Point2::new(x, y))
     }
 
     /// During tests in this module, we're using a world that looks
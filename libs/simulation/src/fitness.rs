@@ -0,0 +1,132 @@
+use crate::{Animal, Config};
+
+/// How well a bird did, used to pick the parents of the next birds.
+///
+/// Set one with `Simulation::set_fitness`; the default is `Satiation`
+/// plus `Survival` weighted by `Config::fitness_survival`.
+pub trait Fitness {
+    fn fitness(&self, animal: &Animal, config: &Config) -> f32;
+}
+
+/// One point per food eaten, minus one per poisonous food, never below 0
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Satiation;
+
+impl Fitness for Satiation {
+    fn fitness(&self, animal: &Animal, _config: &Config) -> f32 {
+        net_foods(animal)
+    }
+}
+
+/// Foods eaten per unit of distance flown, rewarding birds that head
+/// straight for food over the ones flying around at full speed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FoodPerDistance;
+
+impl Fitness for FoodPerDistance {
+    fn fitness(&self, animal: &Animal, _config: &Config) -> f32 {
+        if animal.distance > 0.0 {
+            net_foods(animal) / animal.distance
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Part of the generation the bird stayed alive, from 0 to 1
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Survival;
+
+impl Fitness for Survival {
+    fn fitness(&self, animal: &Animal, config: &Config) -> f32 {
+        (animal.survived as f32 / config.generation_length as f32).min(1.0)
+    }
+}
+
+/// Sum of other fitness functions, each multiplied by a weight; negative
+/// weights work as penalties, but the sum never goes below 0, like
+/// selection needs
+#[derive(Default)]
+pub struct Weighted {
+    parts: Vec<(f32, Box<dyn Fitness>)>,
+}
+
+impl Weighted {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, weight: f32, fitness: impl Fitness + 'static) -> Self {
+        self.parts.push((weight, Box::new(fitness)));
+        self
+    }
+}
+
+impl Fitness for Weighted {
+    fn fitness(&self, animal: &Animal, config: &Config) -> f32 {
+        self.parts
+            .iter()
+            .map(|(weight, fitness)| weight * fitness.fitness(animal, config))
+            .sum::<f32>()
+            .max(0.0)
+    }
+}
+
+/// Foods eaten, with poisonous ones counting against it
+fn net_foods(animal: &Animal) -> f32 {
+    (animal.satiation as f32 - animal.poisoned as f32).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn animal(satiation: usize, distance: f32, survived: usize) -> Animal {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut animal = Animal::random(&mut rng, &Config::default());
+        animal.satiation = satiation;
+        animal.distance = distance;
+        animal.survived = survived;
+        animal
+    }
+
+    #[test]
+    fn built_ins() {
+        let config = Config::default();
+        let animal = animal(4, 2.0, 1250);
+
+        assert_eq!(Satiation.fitness(&animal, &config), 4.0);
+        assert_eq!(FoodPerDistance.fitness(&animal, &config), 2.0);
+        assert_eq!(Survival.fitness(&animal, &config), 0.5);
+
+        let still = self::animal(0, 0.0, 0);
+        assert_eq!(FoodPerDistance.fitness(&still, &config), 0.0);
+
+        let mut poisoned = self::animal(4, 2.0, 1250);
+        poisoned.poisoned = 1;
+        assert_eq!(Satiation.fitness(&poisoned, &config), 3.0);
+        poisoned.poisoned = 6;
+        assert_eq!(Satiation.fitness(&poisoned, &config), 0.0);
+    }
+
+    #[test]
+    fn weighted() {
+        let config = Config::default();
+        let animal = animal(4, 2.0, 1250);
+
+        let fitness = Weighted::new()
+            .with(1.0, Satiation)
+            .with(2.0, Survival)
+            .with(-0.5, FoodPerDistance);
+
+        assert_eq!(fitness.fitness(&animal, &config), 4.0 + 1.0 - 1.0);
+        assert_eq!(Weighted::new().fitness(&animal, &config), 0.0);
+
+        // Penalties can't make it negative
+        let penalty = Weighted::new().with(-1.0, Satiation);
+        assert_eq!(penalty.fitness(&animal, &config), 0.0);
+    }
+}
@@ -1,18 +1,192 @@
+use std::f32::consts::TAU;
+
 use nalgebra as na;
 use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::Config;
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Food {
     pub(crate) position: na::Point2<f32>,
+    /// Index into `Config::food_kinds`
+    pub(crate) kind: usize,
+    /// Steps left until the food grows back after being eaten
+    pub(crate) regrow_in: usize,
+    /// Eaten after the generation's food budget ran out
+    pub(crate) depleted: bool,
+    /// Out of season, see `Config::food_season_length`
+    pub(crate) dormant: bool,
 }
 
 impl Food {
     pub fn random(rng: &mut dyn RngCore) -> Self {
+        Self::at(rng.random())
+    }
+
+    pub(crate) fn at(position: na::Point2<f32>) -> Self {
         Self {
-            position: rng.random(),
+            position,
+            kind: 0,
+            regrow_in: 0,
+            depleted: false,
+            dormant: false,
         }
     }
 
     pub fn position(&self) -> na::Point2<f32> {
         self.position
     }
+
+    /// Index of its kind in `Config::food_kinds`
+    pub fn kind(&self) -> usize {
+        self.kind
+    }
+
+    /// Whether the food is in the world, so birds can see and eat it
+    pub fn is_available(&self) -> bool {
+        self.regrow_in == 0 && !self.depleted && !self.dormant
+    }
+}
+
+/// What a food does to the bird that eats it
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FoodKind {
+    /// How often foods of this kind grow, relative to the other kinds
+    pub share: f32,
+    /// Energy restored when eaten, in multiples of `energy_per_food`;
+    /// negative values drain it instead
+    pub nutrition: f32,
+    /// Eating it takes a point off the bird's fitness instead of adding one
+    pub poisonous: bool,
+}
+
+impl FoodKind {
+    pub const NORMAL: Self = Self {
+        share: 1.0,
+        nutrition: 1.0,
+        poisonous: false,
+    };
+
+    /// Pick the kind of a new food, by their shares
+    pub(crate) fn pick(rng: &mut dyn RngCore, kinds: &[Self]) -> usize {
+        if kinds.len() < 2 {
+            return 0;
+        }
+
+        let total: f32 = kinds.iter().map(|kind| kind.share).sum();
+        let mut roll = rng.random::<f32>() * total;
+
+        for (idx, kind) in kinds.iter().enumerate() {
+            if roll < kind.share {
+                return idx;
+            }
+            roll -= kind.share;
+        }
+
+        // Rounding can leave `roll` just past the last share
+        kinds.len() - 1
+    }
+}
+
+/// An area foods spawn around, drifting across the world over time
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FoodPatch {
+    pub(crate) center: na::Point2<f32>,
+    pub(crate) velocity: na::Vector2<f32>,
+}
+
+impl FoodPatch {
+    pub(crate) fn random(rng: &mut dyn RngCore, config: &Config) -> Self {
+        let direction = na::Rotation2::new(rng.random::<f32>() * TAU);
+
+        Self {
+            center: rng.random(),
+            velocity: direction * na::Vector2::new(0.0, config.food_patch_drift),
+        }
+    }
+
+    pub fn center(&self) -> na::Point2<f32> {
+        self.center
+    }
+
+    pub(crate) fn drift(&mut self) {
+        self.center += self.velocity;
+        self.center.x = na::wrap(self.center.x, 0.0, 1.0);
+        self.center.y = na::wrap(self.center.y, 0.0, 1.0);
+    }
+
+    /// A random point within `radius` of the center, uniformly spread
+    pub(crate) fn sample(&self, rng: &mut dyn RngCore, radius: f32) -> na::Point2<f32> {
+        let direction = na::Rotation2::new(rng.random::<f32>() * TAU);
+        let distance = radius * rng.random::<f32>().sqrt();
+
+        let mut position = self.center + direction * na::Vector2::new(0.0, distance);
+        position.x = na::wrap(position.x, 0.0, 1.0);
+        position.y = na::wrap(position.y, 0.0, 1.0);
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::World;
+
+    #[test]
+    fn patches_keep_foods_close() {
+        let config = Config {
+            food_patches: 1,
+            food_patch_radius: 0.05,
+            ..Config::default()
+        };
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut world = World::random(&mut rng, &config, 0, 100);
+        let center = world.patches()[0].center();
+
+        // Distance on a world that wraps around at the edges
+        let distance = |a: na::Point2<f32>, b: na::Point2<f32>| {
+            let delta = (a - b).map(|d| d.abs().min(1.0 - d.abs()));
+            delta.norm()
+        };
+
+        assert!(
+            world
+                .foods()
+                .iter()
+                .all(|food| distance(food.position(), center) <= 0.05 + 1e-6)
+        );
+
+        world.patches[0].velocity = na::Vector2::new(0.1, 0.0);
+        world.patches[0].drift();
+        assert!((distance(world.patches()[0].center(), center) - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn kinds_follow_their_shares() {
+        let kinds = [
+            FoodKind::NORMAL,
+            FoodKind {
+                share: 3.0,
+                ..FoodKind::NORMAL
+            },
+            FoodKind {
+                share: 0.0,
+                ..FoodKind::NORMAL
+            },
+        ];
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut counts = [0; 3];
+        for _ in 0..4000 {
+            counts[FoodKind::pick(&mut rng, &kinds)] += 1;
+        }
+
+        assert_eq!(counts[2], 0);
+        assert!((2700..3300).contains(&counts[1]), "{counts:?}");
+    }
 }
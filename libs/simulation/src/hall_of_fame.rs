@@ -0,0 +1,181 @@
+use std::{
+    fs,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use lib_genetic_algorithm::{self as ga, Individual};
+use serde::{Deserialize, Serialize};
+
+use crate::AnimalIndividual;
+
+/// The best birds ever seen, across all generations.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HallOfFame {
+    capacity: usize,
+    /// Sorted by fitness, best first
+    entries: Vec<HallOfFameEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HallOfFameEntry {
+    pub fitness: f32,
+    /// Generation the bird lived in, starting at 0
+    pub generation: usize,
+    pub chromosome: ga::Crhomosome,
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn entries(&self) -> &[HallOfFameEntry] {
+        &self.entries
+    }
+
+    pub fn best(&self) -> Option<&HallOfFameEntry> {
+        self.entries.first()
+    }
+
+    /// Add the birds of `population` that are better than the ones
+    /// already in the hall of fame. A bird already in it, like one that
+    /// lives through several steady-state periods, keeps its best entry.
+    pub(crate) fn record(&mut self, generation: usize, population: &[AnimalIndividual]) {
+        for individual in population {
+            let fitness = individual.fitness();
+
+            if let Some(existing) = self
+                .entries
+                .iter()
+                .position(|e| e.chromosome == individual.chromosome)
+            {
+                if self.entries[existing].fitness >= fitness {
+                    continue;
+                }
+                self.entries.remove(existing);
+            }
+
+            if self.entries.len() == self.capacity
+                && self
+                    .entries
+                    .last()
+                    .is_none_or(|worst| fitness <= worst.fitness)
+            {
+                continue;
+            }
+
+            // Earlier entries win ties
+            let index = self.entries.partition_point(|e| e.fitness >= fitness);
+
+            self.entries.insert(
+                index,
+                HallOfFameEntry {
+                    fitness,
+                    generation,
+                    chromosome: individual.chromosome.clone(),
+                },
+            );
+            self.entries.truncate(self.capacity);
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+
+        // Like `Checkpoint::save`, so a crash never truncates the old one
+        let tmp = path.with_extension("tmp");
+        let mut writer = BufWriter::new(fs::File::create(&tmp)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(tmp, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(fs::File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn individual(fitness: f32) -> AnimalIndividual {
+        AnimalIndividual {
+            fitness,
+            chromosome: [fitness].into_iter().collect(),
+        }
+    }
+
+    fn fitnesses(hall_of_fame: &HallOfFame) -> Vec<(f32, usize)> {
+        hall_of_fame
+            .entries()
+            .iter()
+            .map(|e| (e.fitness, e.generation))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_best_across_generations() {
+        let mut hall_of_fame = HallOfFame::new(3);
+
+        hall_of_fame.record(0, &[individual(1.0), individual(4.0)]);
+        assert_eq!(fitnesses(&hall_of_fame), vec![(4.0, 0), (1.0, 0)]);
+
+        // Another bird as good as the best one, not the same one again
+        let rival = AnimalIndividual {
+            fitness: 4.0,
+            chromosome: [4.5].into_iter().collect(),
+        };
+        hall_of_fame.record(1, &[individual(2.0), rival, individual(0.5)]);
+        assert_eq!(fitnesses(&hall_of_fame), vec![(4.0, 0), (4.0, 1), (2.0, 1)]);
+
+        hall_of_fame.record(2, &[individual(2.0), individual(5.0)]);
+        assert_eq!(fitnesses(&hall_of_fame), vec![(5.0, 2), (4.0, 0), (4.0, 1)]);
+        assert_eq!(hall_of_fame.best().unwrap().chromosome[0], 5.0);
+    }
+
+    #[test]
+    fn keeps_a_bird_once() {
+        let mut hall_of_fame = HallOfFame::new(3);
+        let bird = |fitness| AnimalIndividual {
+            fitness,
+            chromosome: [7.0].into_iter().collect(),
+        };
+
+        hall_of_fame.record(0, &[bird(2.0), individual(1.0)]);
+        hall_of_fame.record(1, &[bird(1.5)]);
+        hall_of_fame.record(2, &[bird(3.0)]);
+
+        assert_eq!(fitnesses(&hall_of_fame), vec![(3.0, 2), (1.0, 0)]);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("hall-of-fame-{}.json", std::process::id()));
+
+        let mut hall_of_fame = HallOfFame::new(2);
+        hall_of_fame.record(0, &[individual(1.0), individual(3.0)]);
+        hall_of_fame.save(&path).unwrap();
+
+        let loaded = HallOfFame::load(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(fitnesses(&loaded), fitnesses(&hall_of_fame));
+    }
+
+    #[test]
+    fn empty_capacity() {
+        let mut hall_of_fame = HallOfFame::new(0);
+        hall_of_fame.record(0, &[individual(1.0)]);
+
+        assert!(hall_of_fame.best().is_none());
+    }
+}
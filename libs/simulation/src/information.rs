@@ -1,13 +1,22 @@
 pub struct Information {
     generation: usize,
+    cohesion: f32,
 }
 
 impl Information {
-    pub fn new(generation: usize) -> Self {
-        Self { generation }
+    pub fn new(generation: usize, cohesion: f32) -> Self {
+        Self {
+            generation,
+            cohesion,
+        }
     }
 
     pub fn generation(&self) -> usize {
         self.generation
     }
+
+    /// See `World::cohesion`, measured at the end of the generation
+    pub fn cohesion(&self) -> f32 {
+        self.cohesion
+    }
 }
@@ -1,70 +1,279 @@
 mod animal;
 mod animal_individual;
 mod brain;
+mod checkpoint;
+mod config;
+mod evaluation;
+mod event;
 mod eye;
+mod fitness;
 mod food;
+mod hall_of_fame;
 mod information;
+mod observer;
+mod replay;
 mod world;
 
-use std::f32::consts::FRAC_PI_2;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
 
 use lib_genetic_algorithm as ga;
+use lib_neural_network as nn;
 use nalgebra::{self as na};
-use rand::{Rng, RngCore};
+use rand::{RngCore, seq::IndexedRandom};
 
-use crate::animal_individual::AnimalIndividual;
+use crate::checkpoint::{AnimalState, Checkpointing};
+use crate::world::spawn_food;
 
 pub use self::animal::Animal;
-pub use self::food::Food;
+pub use self::animal_individual::AnimalIndividual;
+pub use self::brain::{Activation, BrainInputs, BrainOutputs, HiddenLayer};
+pub use self::checkpoint::Checkpoint;
+pub use self::config::{Config, ConfigError, Evolution, Selection};
+pub use self::evaluation::{Evaluation, TrajectoryPoint};
+pub use self::event::Event;
+pub use self::fitness::{Fitness, FoodPerDistance, Satiation, Survival, Weighted};
+pub use self::food::{Food, FoodKind, FoodPatch};
+pub use self::hall_of_fame::{HallOfFame, HallOfFameEntry};
 pub use self::information::Information;
+pub use self::observer::Observer;
+pub use self::replay::{
+    AnimalFrame, FoodEaten, FoodFrame, FoodRespawned, Replay, ReplayFrame, ReplayPlayer,
+};
 pub use self::world::World;
 pub use ga::Statistics;
-
-// === Const ===
-const SPEED_MIN: f32 = 0.001;
-const SPEED_MAX: f32 = 0.005;
-const SPEED_ACCEL: f32 = 0.2;
-const ROTATION_ACCEL: f32 = FRAC_PI_2;
-const GENERATION_LENGTH: usize = 2500;
+pub use lib_neural_network::{Layer, Network};
+pub use rand_chacha::ChaCha8Rng;
 
 pub struct Simulation {
+    config: Config,
     world: World,
-    ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
+    ga: ga::GeneticAlgorithm<Selection>,
     age: usize,
     generation: usize,
+    history: Vec<ga::Statistics>,
+    hall_of_fame: HallOfFame,
+    observers: Vec<Box<dyn Observer>>,
+    fitness: Box<dyn Fitness>,
+    checkpointing: Option<Checkpointing>,
+    /// Replay of the current generation, when recording
+    recording: Option<Replay>,
+    last_replay: Option<Replay>,
+    /// Events not drained yet, when recording them
+    events: Option<Vec<Event>>,
 }
 
 impl Simulation {
     /// Create a random simulation
     pub fn random(rng: &mut dyn RngCore, animals_count: usize, food_count: usize) -> Self {
-        let world = World::random(rng, animals_count, food_count);
+        Self::random_with_config(rng, Config::default(), animals_count, food_count)
+            .expect("default config is valid")
+    }
+
+    /// Create a random simulation with custom settings
+    pub fn random_with_config(
+        rng: &mut dyn RngCore,
+        config: Config,
+        animals_count: usize,
+        food_count: usize,
+    ) -> Result<Self, ConfigError> {
+        config.validate()?;
+
+        let world = World::random(rng, &config, animals_count, food_count);
+        Ok(Self::new(config, world))
+    }
+
+    /// Restore a simulation, and the RNG that was driving it if any
+    pub fn from_checkpoint(checkpoint: Checkpoint) -> (Self, Option<ChaCha8Rng>) {
+        let mut simulation = Self::new(checkpoint.config.clone(), checkpoint.world());
+        simulation.age = checkpoint.age;
+        simulation.generation = checkpoint.generation;
+        simulation.history = checkpoint.history;
+        simulation.hall_of_fame = checkpoint.hall_of_fame;
+
+        (simulation, checkpoint.rng)
+    }
+
+    /// Load a checkpoint written by `train_checkpointed` and restore it
+    pub fn resume(path: impl AsRef<Path>) -> io::Result<(Self, Option<ChaCha8Rng>)> {
+        Checkpoint::load(path).map(Self::from_checkpoint)
+    }
+
+    fn new(config: Config, world: World) -> Self {
         let ga = ga::GeneticAlgorithm::new(
-            ga::RouletteWheelSelection,
+            config.selection,
             ga::UniformCrossover,
-            ga::GaussianMutation::new(0.01, 0.3),
+            ga::GaussianMutation::new(config.mutation_chance, config.mutation_coeff),
         );
 
+        let fitness = Weighted::new()
+            .with(1.0, Satiation)
+            .with(config.fitness_survival, Survival);
+
         Self {
+            hall_of_fame: HallOfFame::new(config.hall_of_fame_size),
+            config,
             world,
             ga,
             age: 0,
             generation: 0,
+            history: Vec::new(),
+            observers: Vec::new(),
+            fitness: Box::new(fitness),
+            checkpointing: None,
+            recording: None,
+            last_replay: None,
+            events: None,
         }
     }
 
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     pub fn world(&self) -> &World {
         &self.world
     }
 
+    /// Generations finished so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Statistics of every finished generation, oldest first
+    pub fn history(&self) -> &[ga::Statistics] {
+        &self.history
+    }
+
+    /// The best birds seen so far, across all generations
+    pub fn hall_of_fame(&self) -> &HallOfFame {
+        &self.hall_of_fame
+    }
+
+    /// Make `train_checkpointed` write a checkpoint to `path` every
+    /// `every` generations
+    pub fn enable_checkpoints(&mut self, every: usize, path: impl Into<PathBuf>) {
+        assert!(every > 0);
+
+        self.checkpointing = Some(Checkpointing {
+            every,
+            path: path.into(),
+        });
+    }
+
+    /// Capture the current state; pass the driving RNG to be able to
+    /// resume bit-for-bit
+    pub fn checkpoint(&self, rng: Option<&ChaCha8Rng>) -> Checkpoint {
+        Checkpoint {
+            config: self.config.clone(),
+            generation: self.generation,
+            age: self.age,
+            history: self.history.clone(),
+            hall_of_fame: self.hall_of_fame.clone(),
+            animals: self.world.animals.iter().map(AnimalState::from).collect(),
+            foods: self.world.foods.clone(),
+            food_patches: self.world.patches.clone(),
+            food_respawns: self.world.respawns,
+            rng: rng.cloned(),
+        }
+    }
+
+    /// Start or stop recording a replay of every generation; recording
+    /// starts from the current step
+    pub fn record_replays(&mut self, enabled: bool) {
+        self.recording = enabled.then(|| Replay::new(self.generation, &self.world.foods));
+    }
+
+    /// Replay of the last finished generation, if it was recorded
+    pub fn last_replay(&self) -> Option<&Replay> {
+        self.last_replay.as_ref()
+    }
+
+    /// Start or stop collecting an `Event` for everything that happens;
+    /// collected events pile up until drained
+    pub fn record_events(&mut self, enabled: bool) {
+        self.events = enabled.then(Vec::new);
+    }
+
+    /// Take the events collected since the last call, oldest first
+    pub fn drain_events(&mut self) -> impl Iterator<Item = Event> + '_ {
+        self.events.iter_mut().flat_map(|events| events.drain(..))
+    }
+
+    /// Change how birds are scored at the end of every generation.
+    ///
+    /// Fitness functions aren't part of checkpoints, so set it again after
+    /// resuming one.
+    pub fn set_fitness(&mut self, fitness: impl Fitness + 'static) {
+        self.fitness = Box::new(fitness);
+    }
+
+    /// Everything the brain of the bird at `animal` gets as input right
+    /// now: what its eye sees, then the extra `Config::brain_inputs`
+    pub fn brain_inputs(&self, animal: usize) -> Option<Vec<f32>> {
+        let animal = self.world.animals.get(animal)?;
+
+        let mut inputs = animal.vision(&self.world);
+        animal.senses_into(&self.config, self.age, &mut inputs);
+        Some(inputs)
+    }
+
+    /// Register an observer that gets notified as the simulation runs
+    pub fn add_observer(&mut self, observer: impl Observer + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
     pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<(ga::Statistics, Information)> {
+        if self.age == 0 {
+            for observer in &mut self.observers {
+                observer.on_generation_start(self.generation, &self.world);
+            }
+        }
+
+        if let Some(recording) = &mut self.recording {
+            recording.begin_frame();
+        }
+
+        self.process_foods();
         self.process_collisions(rng);
+        self.process_births(rng);
         self.process_brains();
         self.process_movement();
 
+        if let Some(recording) = &mut self.recording {
+            recording.record_world(&self.world);
+        }
+
+        for observer in &mut self.observers {
+            observer.on_step(self.age, &self.world);
+        }
+
         self.age += 1;
-        if self.age > GENERATION_LENGTH {
+
+        // In steady-state mode the world only resets if every bird died
+        let steady_state = matches!(self.config.evolution, Evolution::SteadyState { .. });
+        let extinct = steady_state && !self.world.animals.iter().any(Animal::is_alive);
+
+        if self.age > self.config.generation_length || extinct {
             self.generation += 1;
-            Some((self.envolve(rng), Information::new(self.generation)))
+            let cohesion = self.world.cohesion();
+
+            let stats = if steady_state && !extinct {
+                self.census(rng)
+            } else {
+                self.envolve(rng)
+            };
+
+            if let Some(events) = &mut self.events {
+                events.push(Event::GenerationEnded {
+                    generation: self.generation,
+                    stats,
+                });
+            }
+
+            Some((stats, Information::new(self.generation, cohesion)))
         } else {
             None
         }
@@ -79,43 +288,331 @@ impl Simulation {
         }
     }
 
+    /// Like `train`, but also writes a checkpoint when one is due
+    pub fn train_checkpointed(
+        &mut self,
+        rng: &mut ChaCha8Rng,
+    ) -> io::Result<(ga::Statistics, Information)> {
+        let summary = self.train(rng);
+
+        if let Some(checkpointing) = &self.checkpointing
+            && self.generation.is_multiple_of(checkpointing.every)
+        {
+            self.checkpoint(Some(rng)).save(&checkpointing.path)?;
+        }
+
+        Ok(summary)
+    }
+
+    /// Move the food patches, let eaten foods grow back and apply the
+    /// current season
+    fn process_foods(&mut self) {
+        for patch in &mut self.world.patches {
+            patch.drift();
+        }
+
+        let in_season = self.foods_in_season();
+        for (food_idx, food) in self.world.foods.iter_mut().enumerate() {
+            food.regrow_in = food.regrow_in.saturating_sub(1);
+            food.dormant = food_idx >= in_season;
+        }
+    }
+
+    /// How many foods are around at this point of the season; it's always
+    /// the first ones, so the same foods come and go every season
+    fn foods_in_season(&self) -> usize {
+        let foods = self.world.foods.len();
+        let length = self.config.food_season_length;
+        if length == 0 {
+            return foods;
+        }
+
+        // Seasons carry on across generations
+        let time = self.generation * (self.config.generation_length + 1) + self.age;
+        let phase = (time % length) as f32 / length as f32;
+        let scarcity = (1.0 - (phase * std::f32::consts::TAU).cos()) / 2.0;
+
+        let abundance = 1.0 - self.config.food_season_amplitude * scarcity;
+        (foods as f32 * abundance).round() as usize
+    }
+
     fn process_collisions(&mut self, rng: &mut dyn RngCore) {
-        for animal in &mut self.world.animals {
-            for food in &mut self.world.foods {
+        let World {
+            animals,
+            foods,
+            patches,
+            respawns,
+        } = &mut self.world;
+
+        for (animal_idx, animal) in animals.iter_mut().enumerate() {
+            if !animal.is_alive() {
+                continue;
+            }
+
+            for (food_idx, food) in foods.iter_mut().enumerate() {
+                // A poisonous food may have just killed the bird
+                if !food.is_available() || !animal.is_alive() || !animal.eating {
+                    continue;
+                }
+
                 let distance = na::distance(&animal.position(), &food.position());
 
                 if distance < 0.01 {
-                    animal.satiation += 1;
-                    food.position = rng.random();
+                    let eaten_at = food.position;
+                    animal.eat(&self.config, &self.config.food_kinds[food.kind]);
+
+                    if let Some(recording) = &mut self.recording {
+                        recording.record_eaten(FoodEaten {
+                            animal: animal_idx,
+                            food: food_idx,
+                            position: eaten_at,
+                            poisonous: self.config.food_kinds[food.kind].poisonous,
+                        });
+                    }
+
+                    if let Some(events) = &mut self.events {
+                        events.push(Event::FoodEaten {
+                            animal: animal_idx,
+                            food: food_idx,
+                            position: eaten_at,
+                        });
+
+                        if !animal.is_alive() {
+                            events.push(Event::AnimalDied { animal: animal_idx });
+                        }
+                    }
+
+                    if self
+                        .config
+                        .food_budget
+                        .is_some_and(|budget| *respawns >= budget)
+                    {
+                        food.depleted = true;
+                        continue;
+                    }
+
+                    *respawns += 1;
+                    *food = Food {
+                        regrow_in: self.config.food_regrowth,
+                        ..spawn_food(rng, patches, &self.config)
+                    };
+
+                    if let Some(recording) = &mut self.recording {
+                        recording.record_respawned(food_idx, food);
+                    }
+
+                    if let Some(events) = &mut self.events {
+                        events.push(Event::FoodRespawned {
+                            food: food_idx,
+                            position: food.position,
+                        });
+                    }
                 }
             }
         }
     }
 
+    /// In steady-state mode, let the birds that ate enough have a child in
+    /// place of the weakest bird
+    fn process_births(&mut self, rng: &mut dyn RngCore) {
+        let Evolution::SteadyState { foods_to_reproduce } = self.config.evolution else {
+            return;
+        };
+
+        for parent_idx in 0..self.world.animals.len() {
+            let parent = &self.world.animals[parent_idx];
+            if parent.fed < foods_to_reproduce || !parent.is_alive() {
+                continue;
+            }
+
+            // Dead birds make room first, then the ones closest to starving,
+            // then the least fit; among birds just as weak, any of them
+            let weakness = |i: usize| {
+                let animal = &self.world.animals[i];
+                (animal.energy, self.fitness.fitness(animal, &self.config))
+            };
+            let others = (0..self.world.animals.len()).filter(|&i| i != parent_idx);
+
+            let Some(weakest) = others
+                .clone()
+                .map(weakness)
+                .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)))
+            else {
+                continue;
+            };
+
+            let weakest: Vec<_> = others.filter(|&i| weakness(i) == weakest).collect();
+            let Some(&weakest_idx) = weakest.choose(rng) else {
+                continue;
+            };
+
+            let population: Vec<_> = self
+                .world
+                .animals
+                .iter()
+                .filter(|animal| animal.is_alive())
+                .map(|animal| self.individual(animal))
+                .collect();
+            let parent = self.individual(parent);
+
+            let position = self.world.animals[parent_idx].positon;
+            let mut child = self
+                .ga
+                .breed(rng, &parent, &population)
+                .into_animal(rng, &self.config);
+            child.positon = position;
+
+            if let Some(events) = &mut self.events {
+                if self.world.animals[weakest_idx].is_alive() {
+                    events.push(Event::AnimalDied {
+                        animal: weakest_idx,
+                    });
+                }
+
+                events.push(Event::AnimalBorn {
+                    animal: weakest_idx,
+                    parent: parent_idx,
+                });
+            }
+
+            self.world.animals[parent_idx].fed = 0;
+            self.world.animals[weakest_idx] = child;
+        }
+    }
+
     fn process_brains(&mut self) {
-        for animal in &mut self.world.animals {
-            let vision =
-                animal
-                    .eye
-                    .process_vision(animal.positon, animal.rotation, &self.world.foods);
+        // Shared by every bird, so thinking doesn't allocate per bird
+        let mut vision = Vec::new();
+        let mut scratch = nn::Scratch::default();
+
+        // Where every bird was before any of them turned, to see each other
+        let flock: Vec<_> = if self.config.eye_birds {
+            self.world
+                .animals
+                .iter()
+                .map(|animal| (animal.sighting(), animal.is_alive()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        for (animal_idx, animal) in self.world.animals.iter_mut().enumerate() {
+            if !animal.is_alive() {
+                continue;
+            }
+
+            animal.eye.process_vision_into(
+                animal.positon,
+                animal.rotation,
+                &self.world.foods,
+                &mut vision,
+            );
+            animal.eye.process_birds_into(
+                animal.positon,
+                animal.rotation,
+                flock
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, &(_, alive))| other != animal_idx && alive)
+                    .map(|(_, &(sighting, _))| sighting),
+                &mut vision,
+            );
+            animal.senses_into(&self.config, self.age, &mut vision);
+
+            let res = animal.brain.nn.propagate_with(&vision, &mut scratch);
+            let speed = res[0].clamp(-self.config.speed_accel, self.config.speed_accel);
+            let rotation = res[1].clamp(-self.config.rotation_accel, self.config.rotation_accel);
 
-            let res = animal.brain.nn.propagate(vision);
-            let speed = res[0].clamp(-SPEED_ACCEL, SPEED_ACCEL);
-            let rotation = res[1].clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+            // the rest of the outputs, in the order `BrainOutputs` lists them
+            let actions = self.config.brain_outputs;
+            let mut extra = res[2..].iter().copied();
+            let mut next = |enabled: bool| enabled.then(|| extra.next().unwrap_or(0.0));
+
+            animal.eating = next(actions.eat).is_none_or(|eat| eat > 0.0);
+            animal.boosting = next(actions.boost).is_some_and(|boost| boost > 0.0);
+            animal.signal = next(actions.signal).map_or(0.0, |signal| signal.clamp(0.0, 1.0));
 
             // rotation absolute to relative
-            animal.speed = (animal.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
+            animal.speed =
+                (animal.speed + speed).clamp(self.config.speed_min, self.config.speed_max);
             animal.rotation = na::Rotation2::new(animal.rotation.angle() + rotation);
         }
     }
 
     fn process_movement(&mut self) {
-        for animal in &mut self.world.animals {
-            animal.positon += animal.rotation * na::Vector2::new(0.0, animal.speed);
+        for (animal_idx, animal) in self.world.animals.iter_mut().enumerate() {
+            if !animal.is_alive() {
+                continue;
+            }
+
+            let speed = animal.flying_speed(&self.config);
+            animal.positon += animal.rotation * na::Vector2::new(0.0, speed);
 
             animal.positon.x = na::wrap(animal.positon.x, 0.0, 1.0);
             animal.positon.y = na::wrap(animal.positon.y, 0.0, 1.0);
+
+            animal.distance += speed;
+            animal.survived += 1;
+            animal.hunger += 1;
+            animal.burn_energy(&self.config);
+
+            if !animal.is_alive()
+                && let Some(events) = &mut self.events
+            {
+                events.push(Event::AnimalDied { animal: animal_idx });
+            }
+        }
+    }
+
+    fn individual(&self, animal: &Animal) -> AnimalIndividual {
+        AnimalIndividual::new(animal, self.fitness.fitness(animal, &self.config))
+    }
+
+    /// Record how the living population is doing, without replacing it
+    fn census(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
+        self.age = 0;
+
+        let population: Vec<_> = self
+            .world
+            .animals
+            .iter()
+            .map(|animal| self.individual(animal))
+            .collect();
+
+        self.hall_of_fame.record(self.generation - 1, &population);
+
+        // Scores and statistics are per period, like they are per generation
+        for animal in &mut self.world.animals {
+            animal.start_period();
         }
+
+        // A new generation comes with a new food budget
+        self.world.respawns = 0;
+        for (food_idx, food) in self.world.foods.iter_mut().enumerate() {
+            if !food.depleted {
+                continue;
+            }
+
+            *food = spawn_food(rng, &self.world.patches, &self.config);
+
+            if let Some(events) = &mut self.events {
+                events.push(Event::FoodRespawned {
+                    food: food_idx,
+                    position: food.position,
+                });
+            }
+        }
+
+        let stats = ga::Statistics::new(&population);
+        self.history.push(stats);
+
+        for observer in &mut self.observers {
+            observer.on_evaluated(&population, &stats);
+        }
+
+        self.rotate_recording();
+
+        stats
     }
 
     fn envolve(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
@@ -126,23 +623,683 @@ impl Simulation {
             .world
             .animals
             .iter()
-            .map(AnimalIndividual::from)
+            .map(|animal| self.individual(animal))
             .collect();
 
+        self.hall_of_fame
+            .record(self.generation - 1, &current_population);
+
         // Step 2: Envolve birdies
         let (envolved_population, stats) = self.ga.envolve(rng, &current_population);
 
+        self.history.push(stats);
+
+        for observer in &mut self.observers {
+            observer.on_evaluated(&current_population, &stats);
+            observer.on_new_population(&envolved_population);
+        }
+
         // Step 3: Bring birdies back from the genetic algorithm
         self.world.animals = envolved_population
             .into_iter()
-            .map(|i| i.into_animal(rng))
+            .map(|i| i.into_animal(rng, &self.config))
             .collect();
 
         // Step 4: Restart foods
-        for food in &mut self.world.foods {
-            food.position = rng.random();
-        }
+        self.world.reset_foods(rng, &self.config);
+
+        self.rotate_recording();
 
         stats
     }
+
+    /// Finish the replay of the last generation and start a new one
+    fn rotate_recording(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            self.last_replay = Some(recording);
+            self.recording = Some(Replay::new(self.generation, &self.world.foods));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// A simulation seeded the same way every time, with the RNG driving it
+    fn simulation(config: Config, animals: usize, foods: usize) -> (Simulation, ChaCha8Rng) {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let simulation = Simulation::random_with_config(&mut rng, config, animals, foods).unwrap();
+
+        (simulation, rng)
+    }
+
+    #[test]
+    fn observer() {
+        #[derive(Default)]
+        struct TestObserver {
+            events: Rc<RefCell<Vec<String>>>,
+        }
+
+        impl Observer for TestObserver {
+            fn on_generation_start(&mut self, generation: usize, _world: &World) {
+                self.events
+                    .borrow_mut()
+                    .push(format!("generation start {generation}"));
+            }
+
+            fn on_step(&mut self, age: usize, _world: &World) {
+                if age == 0 || age == Config::default().generation_length {
+                    self.events.borrow_mut().push(format!("step {age}"));
+                }
+            }
+
+            fn on_evaluated(&mut self, population: &[AnimalIndividual], _stats: &ga::Statistics) {
+                self.events
+                    .borrow_mut()
+                    .push(format!("evaluated {}", population.len()));
+            }
+
+            fn on_new_population(&mut self, population: &[AnimalIndividual]) {
+                self.events
+                    .borrow_mut()
+                    .push(format!("new population {}", population.len()));
+            }
+        }
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let observer = TestObserver::default();
+        let events = observer.events.clone();
+
+        let mut simulation = Simulation::random(&mut rng, 3, 5);
+        simulation.add_observer(observer);
+        simulation.train(&mut rng);
+        simulation.step(&mut rng);
+
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                "generation start 0",
+                "step 0",
+                format!("step {}", Config::default().generation_length).as_str(),
+                "evaluated 3",
+                "new population 3",
+                "generation start 1",
+                "step 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn energy_is_off_by_default() {
+        let (mut simulation, mut rng) = simulation(Config::default(), 10, 60);
+
+        for _ in 0..300 {
+            simulation.step(&mut rng);
+        }
+
+        let animals = simulation.world().animals();
+        assert!(animals.iter().all(|animal| animal.energy() == 1.0));
+        assert!(animals.iter().any(|animal| animal.satiation() > 0));
+
+        // The fitness is just the food eaten
+        for animal in animals {
+            assert_eq!(
+                simulation.individual(animal).fitness,
+                animal.satiation() as f32
+            );
+        }
+    }
+
+    #[test]
+    fn starving_birds_stop() {
+        let config = Config {
+            energy_max: 0.01,
+            energy_idle_cost: 0.001,
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 3, 0);
+
+        for _ in 0..20 {
+            simulation.step(&mut rng);
+        }
+
+        let animal = &simulation.world().animals()[0];
+        let position = animal.position();
+        assert!(!animal.is_alive());
+        assert_eq!(animal.speed(), 0.0);
+        assert_eq!(animal.survived(), 10);
+
+        simulation.step(&mut rng);
+        assert_eq!(simulation.world().animals()[0].position(), position);
+    }
+
+    #[test]
+    fn steady_state() {
+        let config = Config {
+            generation_length: 300,
+            evolution: Evolution::SteadyState {
+                foods_to_reproduce: 1,
+            },
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 20, 60);
+        let foods: Vec<_> = simulation
+            .world()
+            .foods()
+            .iter()
+            .map(Food::position)
+            .collect();
+
+        let (_, info) = simulation.train(&mut rng);
+        assert_eq!(info.generation(), 1);
+        assert_eq!(simulation.history().len(), 1);
+
+        // Birds were born along the way...
+        let animals = simulation.world().animals();
+        assert_eq!(animals.len(), 20);
+        assert!(animals.iter().any(|a| a.survived() < 300));
+
+        // ...and the world wasn't reset, so uneaten foods are still there
+        assert!(
+            simulation
+                .world()
+                .foods()
+                .iter()
+                .any(|food| foods.contains(&food.position()))
+        );
+
+        // Birds that lived through it start the next period from scratch...
+        assert!(
+            animals
+                .iter()
+                .all(|a| a.satiation() == 0 && a.survived() == 0)
+        );
+
+        // ...and are kept in the hall of fame only once
+        simulation.train(&mut rng);
+        let entries = simulation.hall_of_fame().entries();
+        for (i, entry) in entries.iter().enumerate() {
+            assert!(
+                entries[i + 1..]
+                    .iter()
+                    .all(|other| other.chromosome != entry.chromosome)
+            );
+        }
+    }
+
+    #[test]
+    fn births_replace_the_least_fit() {
+        let config = Config {
+            evolution: Evolution::SteadyState {
+                foods_to_reproduce: 1,
+            },
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 5, 0);
+        simulation.record_events(true);
+
+        // With energy off every bird is just as close to starving
+        for (animal, satiation) in simulation.world.animals.iter_mut().zip([3, 0, 2, 0, 5]) {
+            animal.satiation = satiation;
+        }
+
+        let mut replaced = Vec::new();
+        for _ in 0..20 {
+            simulation.world.animals[4].fed = 1;
+            simulation.process_births(&mut rng);
+
+            replaced.extend(simulation.drain_events().filter_map(|event| match event {
+                Event::AnimalBorn { animal, parent } => {
+                    assert_eq!(parent, 4);
+                    Some(animal)
+                }
+                _ => None,
+            }));
+        }
+
+        // Newborns haven't eaten either, so they stay among the weakest
+        assert_eq!(replaced.len(), 20);
+        assert!(replaced.iter().all(|&animal| animal == 1 || animal == 3));
+        assert!(replaced.contains(&1) && replaced.contains(&3));
+    }
+
+    #[test]
+    fn events() {
+        let config = Config {
+            generation_length: 300,
+            energy_idle_cost: 0.005,
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 300);
+        simulation.record_events(true);
+        simulation.train(&mut rng);
+
+        let events: Vec<_> = simulation.drain_events().collect();
+        assert!(simulation.drain_events().next().is_none());
+
+        // Every food eaten respawns right away
+        let eaten = events
+            .windows(2)
+            .filter(|pair| match pair {
+                [
+                    Event::FoodEaten { food, .. },
+                    Event::FoodRespawned {
+                        food: respawned, ..
+                    },
+                ] => food == respawned,
+                _ => false,
+            })
+            .count();
+        assert!(eaten > 0);
+        assert_eq!(
+            eaten,
+            events
+                .iter()
+                .filter(|e| matches!(e, Event::FoodEaten { .. }))
+                .count()
+        );
+
+        // Nobody can last 300 steps on that little energy without eating
+        assert!(events.iter().any(|e| matches!(e, Event::AnimalDied { .. })));
+        assert!(matches!(
+            events.last(),
+            Some(Event::GenerationEnded { generation: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn food_regrowth() {
+        let config = Config {
+            food_regrowth: 50,
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 300);
+        simulation.record_events(true);
+
+        let food = loop {
+            simulation.step(&mut rng);
+            let eaten = simulation.drain_events().find_map(|event| match event {
+                Event::FoodEaten { food, .. } => Some(food),
+                _ => None,
+            });
+
+            if let Some(food) = eaten {
+                break food;
+            }
+        };
+
+        for _ in 0..49 {
+            assert!(!simulation.world().foods()[food].is_available());
+            simulation.step(&mut rng);
+        }
+
+        simulation.step(&mut rng);
+        assert!(simulation.world().foods()[food].is_available());
+    }
+
+    #[test]
+    fn food_budget() {
+        let config = Config {
+            generation_length: 300,
+            food_budget: Some(3),
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 300);
+        simulation.record_events(true);
+        simulation.train(&mut rng);
+
+        let events: Vec<_> = simulation.drain_events().collect();
+        let count = |matches: fn(&Event) -> bool| events.iter().filter(|e| matches(e)).count();
+
+        assert!(count(|e| matches!(e, Event::FoodEaten { .. })) > 3);
+        assert_eq!(count(|e| matches!(e, Event::FoodRespawned { .. })), 3);
+
+        // The next generation starts with every food back
+        assert!(simulation.world().foods().iter().all(Food::is_available));
+    }
+
+    #[test]
+    fn food_seasons() {
+        let config = Config {
+            food_season_length: 100,
+            food_season_amplitude: 1.0,
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 300);
+
+        simulation.step(&mut rng);
+        assert!(simulation.world().foods().iter().any(Food::is_available));
+
+        // Halfway through the cycle is the depth of winter
+        for _ in 0..50 {
+            simulation.step(&mut rng);
+        }
+        assert!(!simulation.world().foods().iter().any(Food::is_available));
+
+        for _ in 0..50 {
+            simulation.step(&mut rng);
+        }
+        assert!(simulation.world().foods().iter().any(Food::is_available));
+    }
+
+    #[test]
+    fn poisonous_food() {
+        let config = Config {
+            food_kinds: vec![FoodKind {
+                share: 1.0,
+                nutrition: -4.0,
+                poisonous: true,
+            }],
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 300);
+        simulation.record_events(true);
+
+        for _ in 0..300 {
+            simulation.step(&mut rng);
+        }
+
+        let animals = simulation.world().animals();
+        assert!(animals.iter().all(|animal| animal.satiation() == 0));
+
+        // A single bite drains all the energy of a bird
+        let poisoned: Vec<_> = (0..animals.len())
+            .filter(|&i| animals[i].poisoned() > 0)
+            .collect();
+        assert!(!poisoned.is_empty());
+        assert!(poisoned.iter().all(|&i| !animals[i].is_alive()));
+
+        let events: Vec<_> = simulation.drain_events().collect();
+        assert!(events.windows(2).any(|pair| matches!(
+            pair,
+            [Event::FoodEaten { animal, .. }, Event::AnimalDied { animal: died }] if animal == died
+        )));
+    }
+
+    #[test]
+    fn birds_see_birds() {
+        let config = Config {
+            generation_length: 100,
+            eye_birds: true,
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 30);
+
+        let world = simulation.world();
+        let vision = world.animals()[0].vision(world);
+        assert_eq!(vision.len(), 3 * eye::CELLS);
+        assert_eq!(
+            world.animals()[0].brain().layers()[0].inputs(),
+            vision.len()
+        );
+
+        // Nothing but a bird right ahead, flying the same way...
+        let animals = &mut simulation.world.animals;
+        for animal in animals.iter_mut() {
+            animal.positon = na::Point2::new(0.1, 0.1);
+        }
+        animals[0].positon = na::Point2::new(0.5, 0.5);
+        animals[0].rotation = na::Rotation2::identity();
+        animals[1].positon = na::Point2::new(0.5, 0.6);
+        animals[1].rotation = na::Rotation2::identity();
+        simulation.world.foods.clear();
+
+        let world = simulation.world();
+        let vision = world.animals()[0].vision(world);
+        let (closeness, heading) = vision[eye::CELLS..].split_at(eye::CELLS);
+        assert!(closeness.iter().sum::<f32>() > 0.0);
+        assert_eq!(heading, closeness);
+
+        // ...then the opposite way
+        simulation.world.animals[1].rotation = na::Rotation2::new(std::f32::consts::PI);
+        let world = simulation.world();
+        let vision = world.animals()[0].vision(world);
+        let (closeness, heading) = vision[eye::CELLS..].split_at(eye::CELLS);
+        for (closeness, heading) in closeness.iter().zip(heading) {
+            assert!((heading + closeness).abs() < 1e-6);
+        }
+
+        let (_, info) = simulation.train(&mut rng);
+        assert!(info.cohesion() > 0.0);
+    }
+
+    #[test]
+    fn extra_brain_inputs() {
+        let config = Config {
+            generation_length: 100,
+            brain_inputs: BrainInputs::ALL,
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 30);
+
+        for age in 0..100 {
+            let inputs = simulation.brain_inputs(0).unwrap();
+            let animal = &simulation.world().animals()[0];
+            assert_eq!(inputs.len(), eye::CELLS + 6);
+            assert_eq!(animal.brain().layers()[0].inputs(), inputs.len());
+
+            let [speed, sin, cos, energy, hunger, clock] = inputs[eye::CELLS..] else {
+                unreachable!();
+            };
+            assert_eq!(speed, animal.speed() / 0.005);
+            assert!((sin * sin + cos * cos - 1.0).abs() < 1e-6);
+            assert_eq!(energy, animal.energy());
+            assert!((0.0..=1.0).contains(&hunger));
+            assert_eq!(clock, age as f32 / 100.0);
+
+            simulation.step(&mut rng);
+        }
+
+        assert!(simulation.brain_inputs(10).is_none());
+    }
+
+    #[test]
+    fn chosen_brain_inputs() {
+        let config = Config {
+            brain_inputs: BrainInputs {
+                compass: true,
+                clock: true,
+                ..BrainInputs::default()
+            },
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 30);
+        for _ in 0..50 {
+            simulation.step(&mut rng);
+        }
+
+        // Only the chosen inputs make it into the brain, in field order
+        let inputs = simulation.brain_inputs(0).unwrap();
+        let [sin, cos, clock] = inputs[eye::CELLS..] else {
+            panic!("expected 3 extra inputs, got {}", inputs.len() - eye::CELLS);
+        };
+        assert!((sin * sin + cos * cos - 1.0).abs() < 1e-6);
+        assert!((0.0..=1.0).contains(&clock));
+    }
+
+    #[test]
+    fn brain_without_hidden_layers() {
+        let config = Config {
+            generation_length: 100,
+            brain_hidden_layers: vec![],
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 30);
+
+        // the eye goes straight into the outputs, generation after generation
+        simulation.train(&mut rng);
+        let layers = simulation.world().animals()[0].brain().layers();
+        assert_eq!(layers.len(), 1);
+        assert_eq!((layers[0].inputs(), layers[0].outputs()), (eye::CELLS, 2));
+    }
+
+    #[test]
+    fn brain_hidden_layers() {
+        let config = Config {
+            generation_length: 100,
+            brain_hidden_layers: vec![
+                HiddenLayer {
+                    neurons: 6,
+                    activation: Activation::Tanh,
+                },
+                HiddenLayer::new(4),
+            ],
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 30);
+
+        // Children are built with the same layers as their parents
+        simulation.train(&mut rng);
+        for animal in simulation.world().animals() {
+            let layers = animal.brain().layers();
+            let sizes: Vec<_> = layers.iter().map(|layer| layer.outputs()).collect();
+            let activations: Vec<_> = layers.iter().map(|layer| layer.activation()).collect();
+            assert_eq!(sizes, vec![6, 4, 2]);
+            assert_eq!(
+                activations,
+                vec![
+                    nn::Activation::Tanh,
+                    nn::Activation::Relu,
+                    nn::Activation::Relu
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn brain_outputs() {
+        let config = Config {
+            brain_outputs: BrainOutputs {
+                eat: true,
+                boost: true,
+                signal: false,
+            },
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 30);
+
+        let layers = simulation.world().animals()[0].brain().layers();
+        assert_eq!(layers.last().unwrap().outputs(), 4);
+        assert_eq!(
+            simulation.config().brain_outputs.names(),
+            ["speed", "rotation", "eat", "boost"]
+        );
+
+        // A bird that doesn't want to eat flies right over food
+        let food = simulation.world.foods[0].position;
+        simulation.world.animals[0].positon = food;
+        simulation.world.animals[0].eating = false;
+        simulation.step(&mut rng);
+        assert_eq!(simulation.world().animals()[0].satiation(), 0);
+        assert_eq!(simulation.world().foods()[0].position(), food);
+
+        // Boosting birds cover `boost_factor` times the distance
+        let mut boosted = 0;
+        for _ in 0..100 {
+            let distances: Vec<_> = simulation
+                .world
+                .animals
+                .iter()
+                .map(|a| a.distance)
+                .collect();
+            simulation.step(&mut rng);
+
+            for (animal, distance) in simulation.world.animals.iter().zip(distances) {
+                let factor = if animal.boosting {
+                    boosted += 1;
+                    simulation.config.boost_factor
+                } else {
+                    1.0
+                };
+
+                let covered = animal.distance - distance;
+                assert!((covered - factor * animal.speed).abs() < 1e-6);
+            }
+        }
+        assert!(boosted > 0);
+    }
+
+    #[test]
+    fn signal_output() {
+        // Signals need eye_birds, or nobody would see them
+        let config = Config {
+            eye_birds: true,
+            brain_outputs: BrainOutputs::ALL,
+            ..Config::default()
+        };
+
+        let (mut simulation, mut rng) = simulation(config, 10, 30);
+        assert_eq!(
+            simulation.config().brain_outputs.names(),
+            ["speed", "rotation", "eat", "boost", "signal"]
+        );
+
+        let world = simulation.world();
+        assert_eq!(world.animals()[0].vision(world).len(), 4 * eye::CELLS);
+
+        for _ in 0..100 {
+            simulation.step(&mut rng);
+        }
+        assert!(
+            simulation
+                .world()
+                .animals()
+                .iter()
+                .all(|animal| (0.0..=1.0).contains(&animal.signal()))
+        );
+    }
+
+    #[test]
+    fn checkpoint_resume() {
+        let path = std::env::temp_dir().join(format!("checkpoint-{}.json", std::process::id()));
+
+        let config = Config {
+            generation_length: 300,
+            ..Config::default()
+        };
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut simulation = Simulation::random_with_config(&mut rng, config, 20, 60).unwrap();
+        simulation.enable_checkpoints(2, &path);
+
+        for _ in 0..4 {
+            simulation.train_checkpointed(&mut rng).unwrap();
+        }
+
+        let (mut resumed, resumed_rng) = Simulation::resume(&path).unwrap();
+        let mut resumed_rng = resumed_rng.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.history().len(), 4);
+
+        for _ in 0..2 {
+            simulation.train(&mut rng);
+            resumed.train(&mut resumed_rng);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&resumed.checkpoint(Some(&resumed_rng))).unwrap(),
+            serde_json::to_string(&simulation.checkpoint(Some(&rng))).unwrap()
+        );
+    }
 }
@@ -0,0 +1,22 @@
+use lib_genetic_algorithm as ga;
+
+use crate::{AnimalIndividual, World};
+
+/// Hooks called by [`crate::Simulation`] while it runs.
+///
+/// Every hook has an empty default, so logging, checkpointing or
+/// visualization tools only implement the ones they need.
+pub trait Observer {
+    /// Called before the first step of every generation.
+    fn on_generation_start(&mut self, _generation: usize, _world: &World) {}
+
+    /// Called after every step, once the animals have moved.
+    fn on_step(&mut self, _age: usize, _world: &World) {}
+
+    /// Called when a generation ends, with the population that is about
+    /// to be evolved.
+    fn on_evaluated(&mut self, _population: &[AnimalIndividual], _stats: &ga::Statistics) {}
+
+    /// Called with the population bred for the next generation.
+    fn on_new_population(&mut self, _population: &[AnimalIndividual]) {}
+}
@@ -0,0 +1,633 @@
+use std::{
+    fs,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use nalgebra as na;
+
+use crate::{Food, World};
+
+/// Identifies replay files, followed by the format version
+const MAGIC: &[u8; 4] = b"BRPL";
+const VERSION: u16 = 4;
+
+/// Everything that happened to the birds and foods of one generation,
+/// step by step.
+///
+/// The binary format is little endian:
+///
+/// ```text
+/// magic     "BRPL"
+/// version   u16
+/// generation u32
+/// foods     u32, then (x: f32, y: f32, kind: u32, available: u8) per food
+/// frames    u32, then per frame:
+///   animals u32, then (x: f32, y: f32, rotation: f32, speed: f32, energy: f32)
+///           per animal
+///   eaten   u32, then (animal: u32, food: u32, x: f32, y: f32, poisonous: u8)
+///           per event
+///   respawned u32, then (food: u32, x: f32, y: f32, kind: u32) per event
+///   available one bit per food, lowest bit first, padded to a whole byte
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    generation: usize,
+    /// Foods before the first frame
+    foods: Vec<FoodFrame>,
+    frames: Vec<ReplayFrame>,
+}
+
+/// State after a single step
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReplayFrame {
+    pub animals: Vec<AnimalFrame>,
+    pub eaten: Vec<FoodEaten>,
+    pub respawned: Vec<FoodRespawned>,
+    /// Whether each food could be eaten after the step; depleted,
+    /// regrowing and dormant foods can't
+    pub available: Vec<bool>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnimalFrame {
+    pub position: na::Point2<f32>,
+    pub rotation: f32,
+    pub speed: f32,
+    pub energy: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FoodEaten {
+    pub animal: usize,
+    pub food: usize,
+    /// Where the food was when it got eaten
+    pub position: na::Point2<f32>,
+    /// Poisoned the bird instead of feeding it
+    pub poisonous: bool,
+}
+
+/// Always after the food was eaten, in the same frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FoodRespawned {
+    pub food: usize,
+    pub position: na::Point2<f32>,
+    /// Index of its kind in `Config::food_kinds`
+    pub kind: usize,
+}
+
+/// A food as a player shows it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FoodFrame {
+    pub position: na::Point2<f32>,
+    /// Index of its kind in `Config::food_kinds`
+    pub kind: usize,
+    pub available: bool,
+}
+
+impl Replay {
+    pub(crate) fn new(generation: usize, foods: &[Food]) -> Self {
+        Self {
+            generation,
+            foods: foods
+                .iter()
+                .map(|food| FoodFrame {
+                    position: food.position,
+                    kind: food.kind,
+                    available: food.is_available(),
+                })
+                .collect(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn frames(&self) -> &[ReplayFrame] {
+        &self.frames
+    }
+
+    pub(crate) fn begin_frame(&mut self) {
+        self.frames.push(ReplayFrame::default());
+    }
+
+    pub(crate) fn record_eaten(&mut self, eaten: FoodEaten) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.eaten.push(eaten);
+        }
+    }
+
+    pub(crate) fn record_respawned(&mut self, food_idx: usize, food: &Food) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.respawned.push(FoodRespawned {
+                food: food_idx,
+                position: food.position,
+                kind: food.kind,
+            });
+        }
+    }
+
+    /// Record the birds, and which foods are available, at the end of a
+    /// step
+    pub(crate) fn record_world(&mut self, world: &World) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.available = world.foods.iter().map(Food::is_available).collect();
+            frame.animals = world
+                .animals
+                .iter()
+                .map(|animal| AnimalFrame {
+                    position: animal.positon,
+                    rotation: animal.rotation.angle(),
+                    speed: animal.speed,
+                    energy: animal.energy,
+                })
+                .collect();
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes)
+            .expect("writing to a Vec can't fail");
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        Self::read_from(bytes)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(fs::File::open(path)?))
+    }
+
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let w = &mut writer;
+
+        w.write_all(MAGIC)?;
+        w.write_all(&VERSION.to_le_bytes())?;
+        write_len(w, self.generation)?;
+
+        write_len(w, self.foods.len())?;
+        for food in &self.foods {
+            write_point(w, &food.position)?;
+            write_len(w, food.kind)?;
+            write_bool(w, food.available)?;
+        }
+
+        write_len(w, self.frames.len())?;
+        for frame in &self.frames {
+            write_len(w, frame.animals.len())?;
+            for animal in &frame.animals {
+                write_point(w, &animal.position)?;
+                write_f32(w, animal.rotation)?;
+                write_f32(w, animal.speed)?;
+                write_f32(w, animal.energy)?;
+            }
+
+            write_len(w, frame.eaten.len())?;
+            for eaten in &frame.eaten {
+                write_len(w, eaten.animal)?;
+                write_len(w, eaten.food)?;
+                write_point(w, &eaten.position)?;
+                write_bool(w, eaten.poisonous)?;
+            }
+
+            write_len(w, frame.respawned.len())?;
+            for respawned in &frame.respawned {
+                write_len(w, respawned.food)?;
+                write_point(w, &respawned.position)?;
+                write_len(w, respawned.kind)?;
+            }
+
+            if frame.available.len() != self.foods.len() {
+                return Err(invalid_data("every food needs its availability"));
+            }
+
+            let mut bits = vec![0; self.foods.len().div_ceil(8)];
+            for (idx, &available) in frame.available.iter().enumerate() {
+                if available {
+                    bits[idx / 8] |= 1 << (idx % 8);
+                }
+            }
+            w.write_all(&bits)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let r = &mut reader;
+
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a replay file"));
+        }
+
+        let mut version = [0; 2];
+        r.read_exact(&mut version)?;
+        if u16::from_le_bytes(version) != VERSION {
+            return Err(invalid_data("unsupported replay version"));
+        }
+
+        let generation = read_len(r)?;
+        let foods = read_vec(r, |r| {
+            Ok(FoodFrame {
+                position: read_point(r)?,
+                kind: read_len(r)?,
+                available: read_bool(r)?,
+            })
+        })?;
+
+        let frames = read_vec(r, |r| {
+            let animals = read_vec(r, |r| {
+                Ok(AnimalFrame {
+                    position: read_point(r)?,
+                    rotation: read_f32(r)?,
+                    speed: read_f32(r)?,
+                    energy: read_f32(r)?,
+                })
+            })?;
+
+            let eaten = read_vec(r, |r| {
+                let eaten = FoodEaten {
+                    animal: read_len(r)?,
+                    food: read_len(r)?,
+                    position: read_point(r)?,
+                    poisonous: read_bool(r)?,
+                };
+
+                if eaten.food >= foods.len() {
+                    return Err(invalid_data("eaten food out of range"));
+                }
+                if eaten.animal >= animals.len() {
+                    return Err(invalid_data("eating animal out of range"));
+                }
+
+                Ok(eaten)
+            })?;
+
+            let respawned = read_vec(r, |r| {
+                let respawned = FoodRespawned {
+                    food: read_len(r)?,
+                    position: read_point(r)?,
+                    kind: read_len(r)?,
+                };
+
+                if respawned.food >= foods.len() {
+                    return Err(invalid_data("respawned food out of range"));
+                }
+
+                Ok(respawned)
+            })?;
+
+            // Sized by the foods already read, so it's safe to preallocate
+            let mut bits = vec![0; foods.len().div_ceil(8)];
+            r.read_exact(&mut bits)?;
+            let available = (0..foods.len())
+                .map(|idx| bits[idx / 8] & (1 << (idx % 8)) != 0)
+                .collect();
+
+            Ok(ReplayFrame {
+                animals,
+                eaten,
+                respawned,
+                available,
+            })
+        })?;
+
+        // Like the worker protocol, anything past the end is an error: the
+        // file was concatenated with something else, or isn't a replay
+        if r.read(&mut [0])? != 0 {
+            return Err(invalid_data("trailing bytes after the last frame"));
+        }
+
+        Ok(Self {
+            generation,
+            foods,
+            frames,
+        })
+    }
+}
+
+/// Walks through a replay, keeping track of where the foods are.
+pub struct ReplayPlayer {
+    replay: Replay,
+    frame: usize,
+    foods: Vec<FoodFrame>,
+    /// Foods eaten by each bird so far
+    satiation: Vec<usize>,
+    /// Poisonous foods eaten by each bird so far
+    poisoned: Vec<usize>,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let foods = replay.foods.clone();
+
+        // Every bird that eats is in its frame, see `Replay::read_from`
+        let animals = replay
+            .frames
+            .iter()
+            .map(|frame| frame.animals.len())
+            .max()
+            .unwrap_or(0);
+
+        Self {
+            replay,
+            frame: 0,
+            foods,
+            satiation: vec![0; animals],
+            poisoned: vec![0; animals],
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Index of the frame `next_frame` will return
+    pub fn position(&self) -> usize {
+        self.frame
+    }
+
+    pub fn rewind(&mut self) {
+        self.frame = 0;
+        self.foods.clone_from(&self.replay.foods);
+        self.satiation.fill(0);
+        self.poisoned.fill(0);
+    }
+
+    /// How much the bird at `animal` has eaten up to the last frame
+    pub fn satiation(&self, animal: usize) -> usize {
+        self.satiation.get(animal).copied().unwrap_or(0)
+    }
+
+    /// How many poisonous foods the bird at `animal` has eaten up to the
+    /// last frame
+    pub fn poisoned(&self, animal: usize) -> usize {
+        self.poisoned.get(animal).copied().unwrap_or(0)
+    }
+
+    /// Advance one step, returning the birds and foods after it
+    pub fn next_frame(&mut self) -> Option<(&[AnimalFrame], &[FoodFrame])> {
+        let frame = self.replay.frames.get(self.frame)?;
+        self.frame += 1;
+
+        for eaten in &frame.eaten {
+            if eaten.poisonous {
+                self.poisoned[eaten.animal] += 1;
+            } else {
+                self.satiation[eaten.animal] += 1;
+            }
+        }
+
+        for respawned in &frame.respawned {
+            let food = &mut self.foods[respawned.food];
+            food.position = respawned.position;
+            food.kind = respawned.kind;
+        }
+
+        for (food, &available) in self.foods.iter_mut().zip(&frame.available) {
+            food.available = available;
+        }
+
+        Some((&frame.animals, &self.foods))
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_f32(writer: &mut dyn Write, value: f32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_bool(writer: &mut dyn Write, value: bool) -> io::Result<()> {
+    writer.write_all(&[value as u8])
+}
+
+fn write_len(writer: &mut dyn Write, value: usize) -> io::Result<()> {
+    let value = u32::try_from(value).map_err(|_| invalid_data("value too large for a replay"))?;
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_point(writer: &mut dyn Write, point: &na::Point2<f32>) -> io::Result<()> {
+    write_f32(writer, point.x)?;
+    write_f32(writer, point.y)
+}
+
+fn read_f32(reader: &mut dyn Read) -> io::Result<f32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(f32::from_le_bytes(bytes))
+}
+
+fn read_bool(reader: &mut dyn Read) -> io::Result<bool> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+
+    match byte[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(invalid_data("invalid flag")),
+    }
+}
+
+fn read_len(reader: &mut dyn Read) -> io::Result<usize> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes) as usize)
+}
+
+fn read_point(reader: &mut dyn Read) -> io::Result<na::Point2<f32>> {
+    Ok(na::Point2::new(read_f32(reader)?, read_f32(reader)?))
+}
+
+/// Read a length followed by that many items
+fn read_vec<T>(
+    reader: &mut dyn Read,
+    mut read_item: impl FnMut(&mut dyn Read) -> io::Result<T>,
+) -> io::Result<Vec<T>> {
+    let len = read_len(reader)?;
+
+    // The length comes from the file, so it's not used to preallocate;
+    // a corrupt one would otherwise abort on a huge allocation
+    let mut items = Vec::new();
+    for _ in 0..len {
+        items.push(read_item(reader)?);
+    }
+
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::{Config, FoodKind, Simulation};
+
+    fn replay() -> Replay {
+        let config = Config {
+            generation_length: 20,
+            ..Config::default()
+        };
+
+        replay_with(config)
+    }
+
+    fn replay_with(config: Config) -> Replay {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut simulation = Simulation::random_with_config(&mut rng, config, 5, 300).unwrap();
+        simulation.record_replays(true);
+        simulation.train(&mut rng);
+
+        simulation.last_replay().unwrap().clone()
+    }
+
+    #[test]
+    fn records_a_generation() {
+        let replay = replay();
+
+        assert_eq!(replay.generation(), 0);
+        assert_eq!(replay.frames().len(), 21);
+        assert!(replay.frames().iter().all(|f| f.animals.len() == 5));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let replay = replay();
+        let bytes = replay.to_bytes();
+
+        assert_eq!(Replay::from_bytes(&bytes).unwrap(), replay);
+        assert!(Replay::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Replay::from_bytes(b"nope").is_err());
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut bytes = replay().to_bytes();
+        bytes.push(0);
+
+        let err = Replay::from_bytes(&bytes).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_eaters_out_of_range() {
+        let mut replay = replay();
+        let frame = replay
+            .frames
+            .iter_mut()
+            .find(|f| !f.eaten.is_empty())
+            .expect("some food gets eaten");
+        frame.eaten[0].animal = u32::MAX as usize;
+
+        let err = Replay::from_bytes(&replay.to_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn player_applies_eaten_foods() {
+        let replay = replay();
+        let (index, eaten) = replay
+            .frames()
+            .iter()
+            .enumerate()
+            .find_map(|(i, f)| f.eaten.first().map(|e| (i, *e)))
+            .expect("some food gets eaten");
+
+        let mut player = ReplayPlayer::new(replay);
+        for _ in 0..index {
+            player.next_frame();
+        }
+
+        let frame = &player.replay().frames()[index];
+        let respawned = frame
+            .respawned
+            .iter()
+            .find(|r| r.food == eaten.food)
+            .copied();
+        let available = frame.available[eaten.food];
+
+        let (_, foods) = player.next_frame().unwrap();
+        assert_eq!(foods[eaten.food].available, available);
+        if let Some(respawned) = respawned {
+            assert_eq!(foods[eaten.food].position, respawned.position);
+        }
+        assert!(player.satiation(eaten.animal) > 0);
+    }
+
+    #[test]
+    fn player_counts_poison_apart() {
+        let config = Config {
+            generation_length: 300,
+            food_kinds: vec![
+                FoodKind::NORMAL,
+                FoodKind {
+                    share: 1.0,
+                    nutrition: 0.0,
+                    poisonous: true,
+                },
+            ],
+            ..Config::default()
+        };
+
+        let replay = replay_with(config);
+        let eaten: Vec<_> = replay.frames().iter().flat_map(|f| &f.eaten).collect();
+        let poisonous = eaten.iter().filter(|e| e.poisonous).count();
+        assert!(poisonous > 0 && poisonous < eaten.len());
+
+        let mut player = ReplayPlayer::new(replay.clone());
+        assert!(player.foods.iter().any(|food| food.kind == 1));
+
+        for frame in replay.frames() {
+            let (_, foods) = player.next_frame().unwrap();
+            for respawned in &frame.respawned {
+                assert_eq!(foods[respawned.food].kind, respawned.kind);
+            }
+        }
+
+        let total = |count: fn(&ReplayPlayer, usize) -> usize| {
+            (0..5).map(|i| count(&player, i)).sum::<usize>()
+        };
+        assert_eq!(total(ReplayPlayer::poisoned), poisonous);
+        assert_eq!(total(ReplayPlayer::satiation), eaten.len() - poisonous);
+    }
+
+    #[test]
+    fn records_eats_past_the_budget() {
+        let config = Config {
+            generation_length: 300,
+            food_budget: Some(3),
+            ..Config::default()
+        };
+
+        let replay = replay_with(config);
+        let count = |f: fn(&ReplayFrame) -> usize| replay.frames().iter().map(f).sum::<usize>();
+
+        assert!(count(|f| f.eaten.len()) > 3);
+        assert_eq!(count(|f| f.respawned.len()), 3);
+
+        // Depleted foods are gone for the rest of the generation
+        let mut player = ReplayPlayer::new(replay.clone());
+        let mut last = None;
+        while let Some((_, foods)) = player.next_frame() {
+            last = Some(foods.to_vec());
+        }
+
+        let depleted = last.unwrap().iter().filter(|f| !f.available).count();
+        assert_eq!(depleted, count(|f| f.eaten.len()) - 3);
+    }
+}
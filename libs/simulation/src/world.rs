@@ -1,26 +1,153 @@
+use crate::Config;
 use crate::animal::Animal;
-use crate::food::Food;
-use rand::RngCore;
+use crate::food::{Food, FoodKind, FoodPatch};
+use nalgebra as na;
+use rand::{Rng, RngCore};
 
 pub struct World {
     pub(crate) animals: Vec<Animal>,
     pub(crate) foods: Vec<Food>,
+    /// Empty unless foods grow in patches
+    pub(crate) patches: Vec<FoodPatch>,
+    /// Foods respawned this generation, see `Config::food_budget`
+    pub(crate) respawns: usize,
 }
 
 impl World {
     /// Create a random world with animals and foods
-    pub fn random(rng: &mut dyn RngCore, animals_count: usize, food_count: usize) -> Self {
-        let animals = (0..animals_count).map(|_| Animal::random(rng)).collect();
-        let foods = (0..food_count).map(|_| Food::random(rng)).collect();
+    pub fn random(
+        rng: &mut dyn RngCore,
+        config: &Config,
+        animals_count: usize,
+        food_count: usize,
+    ) -> Self {
+        let animals = (0..animals_count)
+            .map(|_| Animal::random(rng, config))
+            .collect();
 
-        Self { animals, foods }
+        Self::with_animals(rng, config, animals, food_count)
+    }
+
+    /// Create a world with `animals` and random foods
+    pub(crate) fn with_animals(
+        rng: &mut dyn RngCore,
+        config: &Config,
+        animals: Vec<Animal>,
+        food_count: usize,
+    ) -> Self {
+        let patches = (0..config.food_patches)
+            .map(|_| FoodPatch::random(rng, config))
+            .collect();
+
+        let mut world = Self {
+            animals,
+            foods: Vec::with_capacity(food_count),
+            patches,
+            respawns: 0,
+        };
+
+        for _ in 0..food_count {
+            let food = spawn_food(rng, &world.patches, config);
+            world.foods.push(food);
+        }
+
+        world
     }
 
     pub fn animals(&self) -> &[Animal] {
         &self.animals
     }
 
+    /// Every food, including the ones that aren't available right now
     pub fn foods(&self) -> &[Food] {
         &self.foods
     }
+
+    pub fn patches(&self) -> &[FoodPatch] {
+        &self.patches
+    }
+
+    /// Average distance from every living bird to the closest other one,
+    /// across the edges of the world; the smaller, the tighter the birds
+    /// flock. 0 with fewer than two living birds.
+    pub fn cohesion(&self) -> f32 {
+        let alive: Vec<_> = self
+            .animals
+            .iter()
+            .filter(|animal| animal.is_alive())
+            .map(|animal| animal.positon)
+            .collect();
+
+        if alive.len() < 2 {
+            return 0.0;
+        }
+
+        let nearest = |idx: usize| {
+            alive
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != idx)
+                .map(|(_, &position)| wrapped_distance(alive[idx], position))
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        (0..alive.len()).map(nearest).sum::<f32>() / alive.len() as f32
+    }
+
+    /// Put every food back in the world, as at the start of a generation
+    pub(crate) fn reset_foods(&mut self, rng: &mut dyn RngCore, config: &Config) {
+        self.respawns = 0;
+
+        for food in &mut self.foods {
+            *food = spawn_food(rng, &self.patches, config);
+        }
+    }
+}
+
+/// Distance between two points, going across the edges when shorter
+fn wrapped_distance(a: na::Point2<f32>, b: na::Point2<f32>) -> f32 {
+    (a - b).map(|d| d.abs().min(1.0 - d.abs())).norm()
+}
+
+/// A new food of a random kind, growing anywhere or around a random patch
+pub(crate) fn spawn_food(rng: &mut dyn RngCore, patches: &[FoodPatch], config: &Config) -> Food {
+    let position = if patches.is_empty() {
+        rng.random()
+    } else {
+        let patch = &patches[rng.random_range(0..patches.len())];
+        patch.sample(rng, config.food_patch_radius)
+    };
+
+    Food {
+        kind: FoodKind::pick(rng, &config.food_kinds),
+        ..Food::at(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn cohesion() {
+        let config = Config::default();
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut world = World::random(&mut rng, &config, 3, 0);
+
+        // Two birds close across the left and right edges, one far away
+        world.animals[0].positon = na::Point2::new(0.05, 0.5);
+        world.animals[1].positon = na::Point2::new(0.95, 0.5);
+        world.animals[2].positon = na::Point2::new(0.5, 0.5);
+        assert!((world.cohesion() - (0.1 + 0.1 + 0.45) / 3.0).abs() < 1e-6);
+
+        // Dead birds don't count
+        world.animals[2].energy = 0.0;
+        assert!((world.cohesion() - 0.1).abs() < 1e-6);
+
+        world.animals[1].energy = 0.0;
+        assert_eq!(world.cohesion(), 0.0);
+    }
 }
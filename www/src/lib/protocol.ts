@@ -0,0 +1,107 @@
+// Binary messages understood by `WorkerHost`; see
+// libs/simulation-wasm/src/protocol.rs for the layout.
+
+export type Snapshot = {
+	generation: number;
+	// x, y, rotation, energy per bird
+	animals: Float32Array;
+	// x, y, kind, available (1 or 0) per food, in world order
+	foods: Float32Array;
+};
+
+export type WorkerEvent =
+	| { type: 'snapshot'; snapshot: Snapshot }
+	| {
+			type: 'generation';
+			generation: number;
+			min: number;
+			max: number;
+			avg: number;
+			cohesion: number;
+	  }
+	| { type: 'population'; json: string };
+
+// Must match `protocol::VERSION`; every message starts with it
+export const VERSION = 1;
+
+export const start = () => new Uint8Array([VERSION, 0]);
+export const pause = () => new Uint8Array([VERSION, 1]);
+export const snapshot = () => new Uint8Array([VERSION, 3]);
+export const exportPopulation = () => new Uint8Array([VERSION, 4]);
+
+// The worker runs at most `protocol::MAX_SPEED` steps per tick
+export function setSpeed(steps: number) {
+	const bytes = new Uint8Array(6);
+	bytes.set([VERSION, 2]);
+	new DataView(bytes.buffer).setUint32(2, steps, true);
+	return bytes;
+}
+
+export function importPopulation(json: string) {
+	const text = new TextEncoder().encode(json);
+	const bytes = new Uint8Array(6 + text.length);
+	bytes.set([VERSION, 5]);
+	new DataView(bytes.buffer).setUint32(2, text.length, true);
+	bytes.set(text, 6);
+	return bytes;
+}
+
+export function decodeEvents(bytes: Uint8Array): WorkerEvent[] {
+	const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+	let offset = 0;
+
+	const u8 = () => view.getUint8(offset++);
+	const u32 = () => {
+		const value = view.getUint32(offset, true);
+		offset += 4;
+		return value;
+	};
+	const f32s = (count: number) => {
+		const values = new Float32Array(count);
+		for (let i = 0; i < count; i++) {
+			values[i] = view.getFloat32(offset, true);
+			offset += 4;
+		}
+		return values;
+	};
+	const str = () => {
+		const len = u32();
+		const text = new TextDecoder().decode(bytes.subarray(offset, offset + len));
+		offset += len;
+		return text;
+	};
+
+	if (u8() !== VERSION) {
+		throw new Error('worker speaks another protocol version, reload the page');
+	}
+
+	const events: WorkerEvent[] = [];
+	for (let count = u32(); count > 0; count--) {
+		switch (u8()) {
+			case 0: {
+				const generation = u32();
+				const animals = f32s(u32() * 4);
+				const foods = f32s(u32() * 4);
+				events.push({ type: 'snapshot', snapshot: { generation, animals, foods } });
+				break;
+			}
+			case 1: {
+				const generation = u32();
+				const [min, max, avg, cohesion] = f32s(4);
+				events.push({ type: 'generation', generation, min, max, avg, cohesion });
+				break;
+			}
+			case 2:
+				events.push({ type: 'population', json: str() });
+				break;
+			default:
+				throw new Error('unknown event');
+		}
+	}
+
+	if (offset !== bytes.byteLength) {
+		throw new Error('trailing bytes after events');
+	}
+
+	return events;
+}
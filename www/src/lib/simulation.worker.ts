@@ -0,0 +1,33 @@
+// Runs a simulation off the main thread. Receives encoded commands from
+// `$lib/protocol` and posts back encoded event batches.
+import * as sim from '../../../libs/simulation-wasm/pkg';
+
+const host = new sim.WorkerHost(0, 0);
+
+function post(events: Uint8Array) {
+	// An empty batch is just the version and its count
+	if (events.length > 5) {
+		postMessage(events, { transfer: [events.buffer] });
+	}
+}
+
+function tick() {
+	if (!host.is_running()) return;
+
+	post(host.tick());
+	setTimeout(tick, 0);
+}
+
+onmessage = (e: MessageEvent<Uint8Array>) => {
+	const wasRunning = host.is_running();
+
+	try {
+		post(host.handle(e.data));
+	} catch (err) {
+		console.error(`simulation worker: ${err}`);
+	}
+
+	if (!wasRunning && host.is_running()) {
+		tick();
+	}
+};
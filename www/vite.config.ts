@@ -4,4 +4,9 @@ import wasm from 'vite-plugin-wasm';
 
 export default defineConfig({
   plugins: [sveltekit(), wasm()],
+  // The simulation also runs in a worker, which needs wasm too
+  worker: {
+    format: 'es',
+    plugins: () => [wasm()],
+  },
 });